@@ -0,0 +1,103 @@
+use crate::prelude::*;
+
+/// The number of moves, by both players, after which an unfinished game is scored as a draw.
+///
+/// This stays below 256 because boards count their turns in a `u8`.
+pub const MAX_PLIES: usize = 250;
+
+#[derive(Clone, Debug, Default)]
+/// The results of a match, from the first evaluator's perspective.
+pub struct MatchReport
+{
+    pub wins:   usize,
+    pub losses: usize,
+    pub draws:  usize,
+}
+
+impl MatchReport
+{
+    /// The Elo difference implied by the score, if neither side won or lost every game.
+    pub fn elo(&self) -> Option<f64>
+    {
+        let score = self.score();
+        (0.0 < score && score < 1.0).then(|| -400.0 * (1.0 / score - 1.0).log10())
+    }
+
+    /// The number of games played.
+    pub fn games(&self) -> usize
+    {
+        self.wins + self.losses + self.draws
+    }
+
+    /// The fraction of the points won, counting draws as half a point.
+    pub fn score(&self) -> f64
+    {
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.games().max(1) as f64
+    }
+}
+
+/// Plays a game from the given position until it ends or runs out of moves, returning the final board.
+pub fn play_game<E: Evaluator>(white: &mut E, black: &mut E, start: &Board, args: SearchArgs) -> Result<Board>
+{
+    let mut board = start.clone();
+
+    white.new_game();
+    black.new_game();
+
+    for _ in 0..MAX_PLIES
+    {
+        if !matches!(board.state(), GameState::NotStarted | GameState::InProgress)
+        {
+            break;
+        }
+
+        let evaluator = match board.to_move()
+        {
+            | Player::White => &mut *white,
+            | Player::Black => &mut *black,
+        };
+
+        let mv = evaluator.best_move(&board, args);
+        board.play(&mv)?;
+    }
+
+    Ok(board)
+}
+
+/// Plays a match between two evaluators from the given position, swapping colours after every game, and calling back
+/// with each final board and the running report.
+pub fn play_match<E: Evaluator>(
+    first: &mut E,
+    second: &mut E,
+    start: &Board,
+    games: usize,
+    args: SearchArgs,
+    mut each: impl FnMut(usize, &Board, &MatchReport),
+) -> Result<MatchReport>
+{
+    let mut report = MatchReport::default();
+
+    for game in 0..games
+    {
+        let first_is_white = game % 2 == 0;
+        let board = if first_is_white
+        {
+            play_game(first, second, start, args)?
+        }
+        else
+        {
+            play_game(second, first, start, args)?
+        };
+
+        match (board.state(), first_is_white)
+        {
+            | (GameState::WhiteWins, true) | (GameState::BlackWins, false) => report.wins += 1,
+            | (GameState::WhiteWins, false) | (GameState::BlackWins, true) => report.losses += 1,
+            | _ => report.draws += 1,
+        }
+
+        each(game, &board, &report);
+    }
+
+    Ok(report)
+}
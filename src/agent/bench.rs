@@ -0,0 +1,238 @@
+use std::time::{Duration, Instant};
+
+use crate::prelude::*;
+
+/// A fixed set of midgame positions, used to compare the speed of searches between builds.
+pub const POSITIONS: [&str; 6] = [
+    r"Base+LMP;InProgress;White[8];wL;bP wL/;wQ -wL;bG1 bP/;wM wQ\;bL bP-;wS1 \wQ;bQ \bG1;wM wL-;bB1 bL-;wS2 wL\;bB2 bB1/;wS1 -bQ;bM bB2-",
+    r"Base+LMP;InProgress;Black[9];wL;bP wL\;wA1 wL/;bL bP-;wM wA1-;bQ bP\;wQ -wA1;bG1 bQ-;wB1 wM/;bL bG1-;wP /wQ;bG2 bL/;wG1 \wA1;bA1 /bQ;wG2 \wB1;bG3 /bP;wA2 -wP",
+    r"Base+LMP;InProgress;White[11];wM;bA1 /wM;wS1 wM/;bB1 -bA1;wP -wS1;bS1 /bB1;wQ -wP;bQ bS1-;wS2 \wQ;bL bQ\;wB1 wS1-;bA2 -bS1;wG1 wB1/;bB2 /bL;wB2 wG1-;bM bA2\;wL /wS2;bM wB2\;wA1 \wG1;bS2 /bM",
+    r"Base+LMP;InProgress;Black[12];wM;bS1 \wM;wG1 wM\;bM bS1/;wL wG1\;bS2 \bM;wQ /wM;bQ /bS2;wL bS1-;bA1 bS2/;wL wG1\;bL -bA1;wA1 /wL;bQ /bL;wA2 wG1-;bP bM-;wA2 -wA1;bG1 bA1-;wB1 /wA2;bL bG1\;wS1 /wB1;bA2 bG1/;wG2 /wS1",
+    r"Base+LMP;InProgress;White[14];wS1;bL -wS1;wM wS1\;bA1 -bL;wL wM-;bG1 \bA1;wQ wL-;bQ \bG1;wS2 wQ\;bA2 bQ/;wP \wQ;bA2 wS1/;wA1 wQ-;bA2 wM\;wA2 wA1-;bA2 bL\;wA2 bA2\;bA2 wS2-;wG1 /wS2;bP bG1-;wA1 \bQ;bA3 bA2\;wA3 /wG1;bM /bQ;wA2 -bM;bA1 wL\",
+    r"Base+LMP;InProgress;Black[15];wG1;bG1 wG1-;wB1 /wG1;bG2 bG1-;wA1 -wB1;bS1 bG1\;wQ -wA1;bQ bG2-;wA2 -wQ;bL bS1\;wG2 /wA2;bB1 \bQ;wS1 \wQ;bM bS1-;wS2 wB1\;bS2 bL-;wG3 -wG2;bG2 -wG1;wS1 \wG1;bS2 wS2-;wA3 \wG3;bB2 bG1-;wB2 /wA3;bM bB2;wB2 /wG3;bL bS1-;wA3 wQ\;bA1 bB1/;wP -wG3",
+];
+
+/// Positions where the player to move has a forced win, or only a few moves that avoid losing at once, used to check
+/// that the selective parts of the search do not overlook tactics.
+///
+/// The last two are won by leaving the opponent without a move, with both queens far from surrounded, so that the null
+/// move can be tried on the way. In the last one, Black has nothing in hand and only two free bugs, so their only reply
+/// walks into the lock, which a null move for Black would hide.
+pub const TACTICS: [Tactic; 12] = [
+    Tactic {
+        position:  r"Base+LMP;InProgress;White[26];wA1;bB1 wA1-;wP /wA1;bS1 bB1\;wB1 wP\;bM bB1/;wQ \wA1;bQ bS1\;wL /wQ;bM wQ-;wS1 wB1\;bL bM-;wL bB1-;bM wQ/;wM -wQ;bB2 \bM;wL wB1-;bL wP-;wB2 /wS1;bL wQ-;wB2 /wB1;bL wM\;wL -wB2;bB2 -bM;wL wB1-;bA1 \bM;wB1 wL;bM wQ-;wS2 wB2\;bM bB2-;wB1 wS1-;bB2 wM;wA2 /wS2;bS2 bM-;wB2 wP\;bB2 /bA1;wM -bL;bB1 wA1;wL bQ-;bB2 -wQ;wA2 bS1-;bB1 wP-;wA1 wA2-;bB2 /bA1;wA3 wA1-;bA1 -wM;wA3 /wS2;bA1 bL-;wA3 wB1-;bB2 \bM",
+        solutions: &["wB2 wS1", "wB2 bB1"],
+        depth:     Depth::new(2),
+    },
+    Tactic {
+        position:  r"Base+LMP;InProgress;White[10];wB1;bM /wB1;wQ wB1-;bL -bM;wG1 \wQ;bB1 bM\;wS1 wG1-;bQ bB1-;wQ bM-;bQ wQ-;wA1 \wG1;bB1 wQ\;wB2 wA1-;bP /bB1;wS1 bQ-;bL wB1-;wB1 bL;bS1 /bP",
+        solutions: &["wA1 bB1-"],
+        depth:     Depth::new(2),
+    },
+    Tactic {
+        position:  r"Base+LMP;InProgress;White[10];wG1;bG1 /wG1;wL \wG1;bA1 -bG1;wM wG1-;bS1 bA1\;wQ wM\;bQ bS1-;wB1 wL-;bA1 bQ-;wL wB1-;bA1 wQ\;wG2 \wL;bA1 \wG2;wL bG1-;bA1 wQ-;wM bQ\;bA1 bQ-",
+        solutions: &["wL -bS1"],
+        depth:     Depth::new(2),
+    },
+    Tactic {
+        position:  r"Base+LMP;InProgress;White[14];wL;bB1 -wL;wA1 wL/;bQ /bB1;wB1 wL\;bG1 \bB1;wQ \wA1;bS1 \bG1;wM wA1-;bS1 wQ/;wM bQ-;bG2 /bG1;wM /bG2;bG3 bS1-;wG1 -wM;bG3 -bS1;wB1 bQ-;bG2 /bG3;wS1 \wM;bG2 wQ-;wB1 bQ\;bS1 -bG3;wA2 /wG1;bB2 /bS1;wA2 bQ-;bM bG3/",
+        solutions: &[r"wA1 wM\"],
+        depth:     Depth::new(2),
+    },
+    Tactic {
+        position:  r"Base+LMP;InProgress;White[17];wB1;bG1 /wB1;wM wB1/;bG2 /bG1;wA1 wM/;bM bG2\;wQ wM-;bQ bG2-;wA1 bG1-;bQ wA1\;wA2 wB1-;bQ wA1-;wM wA2;bG3 -bG1;wP -wQ;bA1 bM\;wB1 wA1;bA1 wQ/;wL -wP;bA1 \wQ;wB1 bQ;bA1 wM-;wM bA1;bB1 /bM;wA3 wB1-;bL /bB1;wG1 wB1\;bS1 /bG2;wB1 wA3;bP \bG3;wM wB1;bS1 /bL",
+        solutions: &["wL wQ-"],
+        depth:     Depth::new(2),
+    },
+    Tactic {
+        position:  r"Base+LMP;InProgress;White[11];wG1;bB1 wG1-;wG2 \wG1;bQ bB1\;wM \wG2;bB2 bQ\;wQ /wG1;bA1 /bB2;wQ wG1\;bA1 /wQ;wM bA1-;bB1 wG1;wG3 -wG2;bA1 /bB1;wM wQ;bB1 bA1;wM bB1;bS1 bB2-;wB1 wM\;bS1 wG1-",
+        solutions: &[r"wB1 wQ\", "wB1 /wM", "wB1 wM", "wB1 wQ"],
+        depth:     Depth::new(2),
+    },
+    Tactic {
+        position:  r"Base+LMP;InProgress;White[11];wA1;bA1 wA1-;wP -wA1;bP bA1-;wB1 \wP;bQ bP-;wQ /wP;bM bQ-;wQ wB1-;bS1 bM\;wG1 wQ/;bS2 bS1\;wL wP\;bA2 \bP;wS1 wL\;bA2 wQ-;wS1 bP\;bL \bQ;wL /wP;bA2 -wG1",
+        solutions: &[
+            "wG1 /wL", "wG1 -bA2", "wB1 wQ", "wB1 wP", "wB1 -wP", "wB1 -bA2", "wB1 bA2", "wB1 wL-", "wQ wL-", "wQ /wB1",
+        ],
+        depth:     Depth::new(2),
+    },
+    Tactic {
+        position:  r"Base+LMP;InProgress;White[16];wS1;bG1 wS1/;wQ /wS1;bA1 bG1-;wL -wQ;bL \bA1;wM wL\;bQ bL/;wL wQ-;bM bA1-;wL -wM;bM -wS1;wS2 wM-;bA1 /bM;wM /wL;bG2 bQ-;wA1 -wL;bM wL-;wA1 bL-;bM wQ-;wA1 \bQ;bP bG1-;wG1 wM\;bA2 bP-;wA1 \bG2;bA2 /wG1;wA1 -bQ;bS1 bL-;wA2 -wM;bA2 wL-",
+        solutions: &["wS2 wS1-", "wS2 wG1-"],
+        depth:     Depth::new(2),
+    },
+    Tactic {
+        position:  r"Base+LMP;InProgress;Black[10];wM;bL wM\;wP wM/;bP bL-;wL wP/;bA1 bP\;wQ wL-;bQ bA1\;wS1 wP-;bG1 bA1-;wG1 -wM;bB1 /bA1;wS2 wQ-;bG2 bQ-;wA1 /wG1;bG3 -bB1;wA1 bB1\;bA2 /bG3;wA2 wS1-",
+        solutions: &[r"bG1 bG2\", "bG1 /wA1", r"bG1 bL\", r"bA2 wA2\", "bG2 -bG3", "bG2 bP-"],
+        depth:     Depth::new(2),
+    },
+    Tactic {
+        position:  r"Base+LMP;InProgress;Black[10];wL;bL wL-;wA1 \wL;bQ bL/;wA2 \wA1;bQ bL-;wQ \wA2;bM bQ/;wG1 /wA2;bB1 bM/;wG2 wA2-;bB2 bL\;wG3 wG1\;bG1 bB2-;wQ -wA2;bS1 /bB2;wA2 bQ-;bP bS1\;wA2 -bM",
+        solutions: &[r"bG1 wL\", "bG1 wG2-"],
+        depth:     Depth::new(2),
+    },
+    Tactic {
+        position:  r"Base;InProgress;Black[31];wA1;bG1 wA1-;wS1 \wA1;bG2 bG1-;wS2 wS1/;bS1 bG2/;wQ -wS2;bQ bG2\;wS2 \bS1;bA1 bS1-;wA2 /wQ;bA1 /wA2;wB1 \wQ;bA2 bS1-;wA3 /wA1;bA2 /wA3;wB1 wQ;bA3 bS1-;wB1 wS1;bA3 wQ/;wB2 wS2/;bA1 -wA2;wG1 wA2\;bB1 bS1-;wG2 -wB2;bB1 bS1;wG1 -wQ;bA1 /wA2;wG1 bA1-;bA1 -wA2;wG1 -wQ;bA1 /wA2;wG1 bA1-;bA1 -wA2;wG1 -wQ;bA1 /wA2;wG1 bA1-;bA1 -wA2;wG1 -wQ;bA1 /wA2;wG1 bA1-;bA1 -wA2;wG1 -wQ;bA1 /wA2;wG1 bA1-;bA1 -wA2;wG1 -wQ;bA1 /wA2;wG3 \wG2;bS2 /bA1;wG3 bG2-;bS2 -wG1;wB1 bA1-;bG3 bQ\;wB2 wS2;bG3 /wB2;wS1 bA3-;bA3 \wS1;wG3 /bQ;bB1 wB2;wQ wG1/",
+        solutions: &[r"bA1 \wQ"],
+        depth:     Depth::new(4),
+    },
+    Tactic {
+        position:  r"Base;InProgress;White[50];wB1;bA1 wB1-;wS1 \wB1;bA2 bA1/;wQ -wS1;bA3 bA2/;wB2 \wQ;bQ bA2-;wG1 -wB2;bA3 wS1-;wB1 bA1;bQ wB1-;wA1 wQ\;bA2 /wA1;wA2 \wB2;bS1 /bA2;wA2 bQ-;bS1 wG1\;wB2 bS1;bS2 bA3/;wG1 wB2\;bG1 \bS2;wQ wB2/;bA2 wQ/;wA3 wA1\;bB1 \bA2;wA3 \bB1;bB2 bS2-;wS2 \wA3;bB2 bS2;wS2 bB1-;bG2 bB2-;wG2 /wB1;bB2 /bG1;wG2 bA3-;bG3 bG1-;wG3 wG1\;bG1 /wG3;wS2 \bG3;bG1 /wS2;wA2 bG3-;bB2 bA3;wA2 bQ-;bB2 wS1;wS2 wQ-;bB2 wB2-;wQ \wB2;bG3 wA1-;wB1 /bQ;bB2 wA1;wA3 bB1/;bG3 wA2-;wB1 /bA1;bG2 wS2-;wA3 \bB1;bG2 wQ-;wQ -wB2;bG3 bB2-;wB1 bA1;bB2 wG3-;wA3 bB1/;bG2 wQ\;wQ \wB2;bB2 wG3;wB1 /bQ;bG3 wA2-;wB1 /bA1;bG3 wA1-;wB1 bA1\;bG3 bG1-;wB1 /bA1;bG3 bA2-;wA3 \wQ;bG2 wQ-;wA3 bQ\;bG3 bG1-;wA2 \bB1;bG2 -wQ;wA2 wG2-;bG3 wA1-;wB1 bA1\;bG3 /wB2;wA2 wA3-;bQ \wA2;wA2 -bG2;bB1 -bA2;wA1 /bG3;bB2 wA1-;wA2 -bB1;bG3 bB2\;wA2 bQ/;bG3 wG1-;wS2 bA2/;bG1 bA1-;wG2 /bA1;bS2 bA2-;wA3 bQ\;bS2 bA3/",
+        solutions: &[r"wA2 \bS2", "wA2 bS2-", "wA2 bS2/", r"wA3 \bS2", "wA3 bS2-", "wA3 bS2/"],
+        depth:     Depth::new(4),
+    },
+];
+
+#[derive(Clone, Copy, Debug)]
+/// A position from the tactical suite, every move that solves it, and how deep a search has to look to find one.
+pub struct Tactic
+{
+    pub position:  &'static str,
+    pub solutions: &'static [&'static str],
+    pub depth:     Depth,
+}
+
+#[derive(Clone, Debug, Default)]
+/// The totals from searching the benchmark positions.
+pub struct BenchReport
+{
+    pub nodes:   u64,
+    pub elapsed: Duration,
+}
+
+impl BenchReport
+{
+    /// The number of nodes searched per second over the whole benchmark.
+    pub fn nodes_per_second(&self) -> f64
+    {
+        self.nodes as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+/// The totals from searching the benchmark positions for a fixed time with some number of threads.
+pub struct ScalingReport
+{
+    pub threads: usize,
+    pub nodes:   u64,
+    pub depth:   f64,
+    pub agreed:  usize,
+}
+
+impl ScalingReport
+{
+    /// The number of nodes searched per second by all threads, over the whole benchmark.
+    pub fn nodes_per_second(&self, time: Duration) -> f64
+    {
+        self.nodes as f64 / (POSITIONS.len() as f64 * time.as_secs_f64()).max(f64::EPSILON)
+    }
+}
+
+/// Searches each benchmark position to a fixed depth, as a new game, calling back with the report for each position as it finishes.
+pub fn bench<E: Evaluator>(evaluator: &mut E, depth: Depth, mut each: impl FnMut(usize, &BenchReport)) -> Result<BenchReport>
+{
+    let mut total = BenchReport::default();
+
+    for (i, position) in POSITIONS.iter().enumerate()
+    {
+        let board: Board = position.parse::<GameString>()?.into();
+
+        evaluator.new_game();
+
+        let start = Instant::now();
+        evaluator.best_move(&board, SearchArgs::Depth(depth));
+
+        let report = BenchReport {
+            nodes:   evaluator.nodes(),
+            elapsed: start.elapsed(),
+        };
+        each(i, &report);
+
+        total.nodes += report.nodes;
+        total.elapsed += report.elapsed;
+    }
+
+    Ok(total)
+}
+
+/// Searches each benchmark position for a fixed time with each number of threads, calling back with the report for each
+/// number of threads as it finishes.
+///
+/// Strength is measured by the average depth completed, and by how many positions the best move agrees with the one
+/// found by the largest number of threads. The evaluator's options are restored afterwards.
+pub fn scaling<E: Evaluator>(
+    evaluator: &mut E,
+    options: &UhpOptions,
+    threads: &[usize],
+    time: Duration,
+    mut each: impl FnMut(&ScalingReport),
+) -> Result<Vec<ScalingReport>>
+{
+    let boards = POSITIONS
+        .iter()
+        .map(|position| position.parse::<GameString>().map(Board::from))
+        .collect::<Result<Vec<Board>>>()?;
+
+    let mut runs = Vec::new();
+
+    for &count in threads
+    {
+        let mut with_threads = options.clone();
+        with_threads.num_threads = count;
+        evaluator.set_options(&with_threads);
+
+        let mut report = ScalingReport {
+            threads: count,
+            ..Default::default()
+        };
+        let mut moves = Vec::new();
+
+        for board in boards.iter()
+        {
+            evaluator.new_game();
+            moves.push(evaluator.best_move(board, SearchArgs::Time(time)));
+
+            report.nodes += evaluator.nodes();
+            report.depth += evaluator.depth().floor() as f64 / boards.len() as f64;
+        }
+
+        runs.push((report, moves));
+    }
+
+    evaluator.set_options(options);
+
+    let reference = runs.iter().max_by_key(|(report, _)| report.threads).map(|(_, moves)| moves.clone()).unwrap_or_default();
+    let reports = runs
+        .into_iter()
+        .map(|(mut report, moves)| {
+            report.agreed = moves.iter().zip(reference.iter()).filter(|(a, b)| a == b).count();
+            each(&report);
+            report
+        })
+        .collect();
+
+    Ok(reports)
+}
+
+/// Searches each tactical position to a fixed depth, or deeper for those that need it, as a new game, calling back with
+/// whether the move found solves each position. Returns how many positions were solved.
+pub fn tactics<E: Evaluator>(evaluator: &mut E, depth: Depth, mut each: impl FnMut(usize, &str, bool)) -> Result<usize>
+{
+    let mut solved = 0;
+
+    for (i, tactic) in TACTICS.iter().enumerate()
+    {
+        let board: Board = tactic.position.parse::<GameString>()?.into();
+
+        evaluator.new_game();
+
+        let depth = depth.max(tactic.depth);
+        let mv = MoveString::from(evaluator.best_move(&board, SearchArgs::Depth(depth))).to_string();
+        let solves = tactic.solutions.contains(&mv.as_str());
+        each(i, &mv, solves);
+
+        solved += solves as usize;
+    }
+
+    Ok(solved)
+}
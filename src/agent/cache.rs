@@ -0,0 +1,205 @@
+use std::sync::{
+    atomic::{AtomicU64, AtomicUsize, Ordering},
+    Arc,
+    Mutex,
+    MutexGuard,
+    PoisonError,
+};
+
+use crate::prelude::*;
+
+#[derive(Clone, Debug, Default)]
+/// Everything the cache knows about a single position.
+struct CacheEntry
+{
+    key:       ZobristHash,
+    eval:      Option<i32>,
+    movements: Option<Arc<[Move]>>,
+}
+
+#[derive(Debug, Default)]
+/// Hit and miss counts for one kind of lookup.
+pub struct CacheCounter
+{
+    hits:   AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CacheCounter
+{
+    /// The number of lookups that found what they were looking for.
+    pub fn hits(&self) -> u64
+    {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// The percentage of lookups that hit, or zero if there were none.
+    pub fn hit_rate(&self) -> f64
+    {
+        let lookups = self.lookups();
+        if lookups == 0
+        {
+            0.0
+        }
+        else
+        {
+            100.0 * self.hits() as f64 / lookups as f64
+        }
+    }
+
+    /// The total number of lookups.
+    pub fn lookups(&self) -> u64
+    {
+        self.hits() + self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Records the result of a lookup.
+    fn record(&self, hit: bool)
+    {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Forgets all lookups.
+    fn reset(&self)
+    {
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug)]
+/// A concurrent cache of static evaluations and movement lists, shared between the search threads.
+///
+/// Both only depend on the position, so unlike the transposition table, entries stay valid between searches.
+pub struct PositionCache
+{
+    slots:          Box<[Mutex<CacheEntry>]>,
+    movement_bytes: AtomicUsize,
+    pub evals:      CacheCounter,
+    pub movements:  CacheCounter,
+}
+
+impl PositionCache
+{
+    /// A guess at the average number of movements in a position, used to size the cache.
+    const AVERAGE_MOVEMENTS: usize = 48;
+
+    /// The approximate size in bytes of a full entry.
+    pub const ENTRY_SIZE: usize = std::mem::size_of::<Mutex<CacheEntry>>() + Self::AVERAGE_MOVEMENTS * std::mem::size_of::<Move>();
+
+    /// Forgets every entry, as well as the hit and miss counts.
+    pub fn clear(&self)
+    {
+        for slot in self.slots.iter()
+        {
+            *Self::lock(slot) = CacheEntry::default();
+        }
+        self.movement_bytes.store(0, Ordering::Relaxed);
+        self.reset_counters();
+    }
+
+    /// Looks up the static evaluation of a position, computing and remembering it on a miss.
+    pub fn eval(&self, board: &Board, evaluate: impl FnOnce(&Board) -> i32) -> i32
+    {
+        let key = board.zobrist();
+        let cached = self.get(key).and_then(|entry| entry.eval);
+        self.evals.record(cached.is_some());
+
+        cached.unwrap_or_else(|| {
+            let eval = evaluate(board);
+            self.update(key, |entry| entry.eval = Some(eval));
+            eval
+        })
+    }
+
+    /// The memory in bytes used by the cache right now, including the movement lists it holds.
+    pub fn memory(&self) -> usize
+    {
+        std::mem::size_of_val(&*self.slots) + self.movement_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Looks up the movements available in a position, without generating them on a miss.
+    pub fn movements(&self, key: ZobristHash) -> Option<Arc<[Move]>>
+    {
+        let cached = self.get(key).and_then(|entry| entry.movements);
+        self.movements.record(cached.is_some());
+        cached
+    }
+
+    /// Creates a new cache with the given memory constraints.
+    pub fn new(bytes: usize) -> PositionCache
+    {
+        let count = (bytes / Self::ENTRY_SIZE).max(1);
+        log::trace!("Allocated a PositionCache with {} entries. ({} bytes)", count, bytes);
+
+        PositionCache {
+            slots:          std::iter::repeat_with(Mutex::default).take(count).collect(),
+            movement_bytes: AtomicUsize::new(0),
+            evals:          CacheCounter::default(),
+            movements:      CacheCounter::default(),
+        }
+    }
+
+    /// Forgets the hit and miss counts, but not the entries.
+    pub fn reset_counters(&self)
+    {
+        self.evals.reset();
+        self.movements.reset();
+    }
+
+    /// Remembers the movements available in a position.
+    pub fn store_movements(&self, key: ZobristHash, movements: Arc<[Move]>)
+    {
+        let bytes = std::mem::size_of_val(&*movements);
+        self.movement_bytes.fetch_add(bytes, Ordering::Relaxed);
+
+        self.update(key, |entry| {
+            if let Some(old) = entry.movements.replace(movements)
+            {
+                self.movement_bytes.fetch_sub(std::mem::size_of_val(&*old), Ordering::Relaxed);
+            }
+        });
+    }
+}
+
+impl PositionCache
+{
+    /// Gets a copy of the entry for this exact position, if there is one.
+    fn get(&self, key: ZobristHash) -> Option<CacheEntry>
+    {
+        let entry = Self::lock(self.slot(key));
+        (entry.key == key).then(|| entry.clone())
+    }
+
+    /// Locks a slot. A thread that panicked while holding it cannot have left it half-written, so poisoning is ignored.
+    fn lock(slot: &Mutex<CacheEntry>) -> MutexGuard<'_, CacheEntry>
+    {
+        slot.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the slot that a key lives in.
+    fn slot(&self, key: ZobristHash) -> &Mutex<CacheEntry>
+    {
+        &self.slots[(key % self.slots.len() as u128) as usize]
+    }
+
+    /// Updates the entry for a position, evicting whichever position was in its slot before.
+    fn update(&self, key: ZobristHash, f: impl FnOnce(&mut CacheEntry))
+    {
+        let mut entry = Self::lock(self.slot(key));
+        if entry.key != key
+        {
+            if let Some(old) = &entry.movements
+            {
+                self.movement_bytes.fetch_sub(std::mem::size_of_val(&**old), Ordering::Relaxed);
+            }
+
+            *entry = CacheEntry {
+                key,
+                ..Default::default()
+            };
+        }
+        f(&mut entry);
+    }
+}
@@ -0,0 +1,115 @@
+use std::path::Path;
+
+use crate::prelude::*;
+
+#[derive(Clone, Debug)]
+/// A finished game and its result, as used to tune and test evaluations.
+///
+/// Games are stored one per line as a result followed by a GameString, such as `1-0 Base+MLP;WhiteWins;...`. The result
+/// can be left out if the GameString itself records how the game ended.
+pub struct GameRecord
+{
+    pub board:  Board,
+    /// The result from White's perspective: 1 for a win, 0.5 for a draw and 0 for a loss.
+    pub result: f64,
+}
+
+impl GameRecord
+{
+    /// Records a game as it ended, scoring an unfinished game as a draw.
+    pub fn new(board: Board) -> GameRecord
+    {
+        let result = match board.state()
+        {
+            | GameState::WhiteWins => 1.0,
+            | GameState::BlackWins => 0.0,
+            | _ => 0.5,
+        };
+
+        GameRecord { board, result }
+    }
+
+    /// Every position before the end of the game, starting from the given turn.
+    pub fn positions(&self, skip: u8) -> Vec<Board>
+    {
+        let mut board = self.board.clone();
+        let mut positions = Vec::new();
+
+        while board.turn() > skip && board.undo_one().is_ok()
+        {
+            positions.push(board.clone());
+        }
+
+        positions.reverse();
+        positions
+    }
+}
+
+impl std::fmt::Display for GameRecord
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        let result = if self.result == 1.0
+        {
+            "1-0"
+        }
+        else if self.result == 0.0
+        {
+            "0-1"
+        }
+        else
+        {
+            "1/2-1/2"
+        };
+
+        write!(f, "{} {}", result, GameString::from(&self.board))
+    }
+}
+
+impl FromStr for GameRecord
+{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self>
+    {
+        let (result, gamestring) = match s.trim().split_once(' ')
+        {
+            | Some(("1-0", rest)) => (Some(1.0), rest),
+            | Some(("0-1", rest)) => (Some(0.0), rest),
+            | Some(("1/2-1/2", rest)) => (Some(0.5), rest),
+            | _ => (None, s.trim()),
+        };
+
+        let board: Board = gamestring.parse::<GameString>()?.into();
+
+        let result = match (result, board.state())
+        {
+            | (Some(result), _) => result,
+            | (None, GameState::WhiteWins | GameState::BlackWins | GameState::Draw) => GameRecord::new(board.clone()).result,
+            | (None, _) => return Err(Error::new(Kind::ParseError, format!("'{}' does not say how the game ended.", s))),
+        };
+
+        Ok(GameRecord { board, result })
+    }
+}
+
+/// Reads a file of games, one per line, skipping blank lines and lines starting with `#`.
+pub fn read_corpus(path: &Path) -> Result<Vec<GameRecord>>
+{
+    let contents = std::fs::read_to_string(path).map_err(|err| {
+        let base = Error::new(Kind::InvalidOption, format!("Could not read games from '{}'.", path.display()));
+        Error::from(err).chain(base)
+    })?;
+
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            line.parse::<GameRecord>().map_err(|err| {
+                let base = Error::new(Kind::ParseError, format!("Could not read line {} of '{}'.", i + 1, path.display()));
+                err.chain(base)
+            })
+        })
+        .collect()
+}
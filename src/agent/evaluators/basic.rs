@@ -8,7 +8,7 @@ pub struct BasicEvaluator;
 
 impl Evaluator for BasicEvaluator
 {
-    type Generator = BasicMoveGenerator;
+    type Generator<'a> = BasicMoveGenerator;
 
     fn best_move(&mut self, board: &Board, _args: SearchArgs) -> Move
     {
@@ -21,7 +21,7 @@ impl Evaluator for BasicEvaluator
         }
     }
 
-    fn generate_moves(board: &Board) -> Self::Generator
+    fn generate_moves(board: &Board) -> Self::Generator<'_>
     {
         BasicMoveGenerator::new(board, false)
     }
@@ -0,0 +1,226 @@
+use std::path::Path;
+
+use crate::prelude::*;
+
+/// The object-safe part of [`Evaluator`], so that evaluators can be chosen at runtime. Every evaluator has it.
+pub trait DynEvaluator
+{
+    fn best_move(&mut self, board: &Board, args: SearchArgs) -> Move;
+
+    fn clear(&mut self);
+
+    fn depth(&self) -> Depth;
+
+    fn load_table(&mut self, path: &Path) -> Result<usize>;
+
+    fn memory(&self) -> usize;
+
+    fn name(&self) -> &'static str;
+
+    fn new_game(&mut self);
+
+    fn nodes(&self) -> u64;
+
+    fn save_table(&self, path: &Path) -> Result<usize>;
+
+    fn set_options(&mut self, options: &UhpOptions);
+}
+
+impl<E: Evaluator> DynEvaluator for E
+{
+    fn best_move(&mut self, board: &Board, args: SearchArgs) -> Move
+    {
+        Evaluator::best_move(self, board, args)
+    }
+
+    fn clear(&mut self)
+    {
+        Evaluator::clear(self)
+    }
+
+    fn depth(&self) -> Depth
+    {
+        Evaluator::depth(self)
+    }
+
+    fn load_table(&mut self, path: &Path) -> Result<usize>
+    {
+        Evaluator::load_table(self, path)
+    }
+
+    fn memory(&self) -> usize
+    {
+        Evaluator::memory(self)
+    }
+
+    fn name(&self) -> &'static str
+    {
+        Evaluator::name(self)
+    }
+
+    fn new_game(&mut self)
+    {
+        Evaluator::new_game(self)
+    }
+
+    fn nodes(&self) -> u64
+    {
+        Evaluator::nodes(self)
+    }
+
+    fn save_table(&self, path: &Path) -> Result<usize>
+    {
+        Evaluator::save_table(self, path)
+    }
+
+    fn set_options(&mut self, options: &UhpOptions)
+    {
+        Evaluator::set_options(self, options)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+/// The evaluators that can be chosen at runtime.
+pub enum EvaluatorKind
+{
+    /// Plays the first legal move; only useful for testing.
+    Basic,
+    /// Alpha-beta search, scoring positions with a weighted sum of features.
+    Linear,
+    /// Monte-Carlo tree search.
+    Mcts,
+    #[default]
+    /// Alpha-beta search, scoring positions with the hand-written heuristics.
+    Strongest,
+}
+
+impl EvaluatorKind
+{
+    /// Creates an evaluator of this kind.
+    pub fn create(&self, options: UhpOptions) -> Box<dyn DynEvaluator>
+    {
+        match self
+        {
+            | EvaluatorKind::Basic => Box::new(super::Basic::new(options)),
+            | EvaluatorKind::Linear => Box::new(super::Linear::new(options)),
+            | EvaluatorKind::Mcts => Box::new(super::Mcts::new(options)),
+            | EvaluatorKind::Strongest => Box::new(super::Strongest::new(options)),
+        }
+    }
+
+    /// The name of this kind, as given to `--evaluator` and the `Evaluator` option.
+    pub fn name(&self) -> &'static str
+    {
+        match self
+        {
+            | EvaluatorKind::Basic => "basic",
+            | EvaluatorKind::Linear => "linear",
+            | EvaluatorKind::Mcts => "mcts",
+            | EvaluatorKind::Strongest => "strongest",
+        }
+    }
+
+    /// Every kind, in the order they are listed as option values.
+    pub fn all() -> [EvaluatorKind; 4]
+    {
+        [EvaluatorKind::Basic, EvaluatorKind::Linear, EvaluatorKind::Mcts, EvaluatorKind::Strongest]
+    }
+}
+
+impl FromStr for EvaluatorKind
+{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self>
+    {
+        Self::all()
+            .into_iter()
+            .find(|kind| kind.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| Error::for_parse::<Self>(s.into()))
+    }
+}
+
+/// An evaluator that hands everything to whichever evaluator the options name, and swaps it out when they change.
+pub struct DynamicEvaluator
+{
+    kind:  EvaluatorKind,
+    inner: Box<dyn DynEvaluator>,
+}
+
+impl Evaluator for DynamicEvaluator
+{
+    type Generator<'a> = super::BasicMoveGenerator;
+
+    fn best_move(&mut self, board: &Board, args: SearchArgs) -> Move
+    {
+        self.inner.best_move(board, args)
+    }
+
+    fn clear(&mut self)
+    {
+        self.inner.clear();
+    }
+
+    fn depth(&self) -> Depth
+    {
+        self.inner.depth()
+    }
+
+    fn generate_moves(board: &Board) -> Self::Generator<'_>
+    {
+        super::BasicMoveGenerator::new(board, false)
+    }
+
+    fn load_table(&mut self, path: &Path) -> Result<usize>
+    {
+        self.inner.load_table(path)
+    }
+
+    fn memory(&self) -> usize
+    {
+        self.inner.memory()
+    }
+
+    fn name(&self) -> &'static str
+    {
+        self.inner.name()
+    }
+
+    fn new(options: UhpOptions) -> Self
+    {
+        let kind = options.evaluator;
+
+        DynamicEvaluator {
+            kind,
+            inner: kind.create(options),
+        }
+    }
+
+    fn new_game(&mut self)
+    {
+        self.inner.new_game();
+    }
+
+    fn nodes(&self) -> u64
+    {
+        self.inner.nodes()
+    }
+
+    fn save_table(&self, path: &Path) -> Result<usize>
+    {
+        self.inner.save_table(path)
+    }
+
+    fn set_options(&mut self, options: &UhpOptions)
+    {
+        if options.evaluator == self.kind
+        {
+            self.inner.set_options(options);
+        }
+        else
+        {
+            log::info!("switching to the {} evaluator", options.evaluator.name());
+            *self = DynamicEvaluator::new(options.clone());
+        }
+    }
+}
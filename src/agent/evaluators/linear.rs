@@ -0,0 +1,306 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::strongest::{LeafEvaluation, StrongestEvaluator};
+use crate::prelude::*;
+
+/// The number of features extracted from each position.
+pub const FEATURE_COUNT: usize = 28;
+
+const BUGS: usize = 8;
+
+const MOBILITY: usize = 0;
+const PINNED: usize = MOBILITY + BUGS;
+const IN_HAND: usize = PINNED + BUGS;
+const QUEEN_LIBERTIES: usize = IN_HAND + BUGS;
+const QUEEN_COVERED: usize = QUEEN_LIBERTIES + 1;
+const PILLBUG_THREATS: usize = QUEEN_COVERED + 1;
+const TEMPO: usize = PILLBUG_THREATS + 1;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// The features of a position that the linear evaluator scores, each as the difference between the player to move and
+/// their opponent.
+pub struct Features(pub [f64; FEATURE_COUNT]);
+
+impl Features
+{
+    /// The name of each feature, in order.
+    pub const NAMES: [&'static str; FEATURE_COUNT] = [
+        "mobility_ant",
+        "mobility_beetle",
+        "mobility_grasshopper",
+        "mobility_ladybug",
+        "mobility_mosquito",
+        "mobility_pillbug",
+        "mobility_queen",
+        "mobility_spider",
+        "pinned_ant",
+        "pinned_beetle",
+        "pinned_grasshopper",
+        "pinned_ladybug",
+        "pinned_mosquito",
+        "pinned_pillbug",
+        "pinned_queen",
+        "pinned_spider",
+        "in_hand_ant",
+        "in_hand_beetle",
+        "in_hand_grasshopper",
+        "in_hand_ladybug",
+        "in_hand_mosquito",
+        "in_hand_pillbug",
+        "in_hand_queen",
+        "in_hand_spider",
+        "queen_liberties",
+        "queen_covered",
+        "pillbug_threats",
+        "tempo",
+    ];
+
+    /// Extracts the features of a position.
+    pub fn extract(board: &Board) -> Features
+    {
+        let to_move = board.to_move();
+        let mut features = [0.0; FEATURE_COUNT];
+
+        let occupied: HashSet<Hex> = board.field().clone().into();
+
+        for piece in occupied.into_iter().filter_map(|hex| board.top(hex))
+        {
+            let sign = if piece.player == to_move { 1.0 } else { -1.0 };
+            let bug = piece.kind as usize;
+
+            if board.is_pinned(&piece)
+            {
+                features[PINNED + bug] += sign;
+            }
+            else
+            {
+                features[MOBILITY + bug] += sign * board.mobility(&piece) as f64;
+            }
+        }
+
+        for player in [to_move, to_move.flip()]
+        {
+            let sign = if player == to_move { 1.0 } else { -1.0 };
+
+            for (bug, count) in board.pouch().hand(player).iter().enumerate()
+            {
+                features[IN_HAND + bug] += sign * *count as f64;
+            }
+
+            // Everything else is about the player's queen. Covering it or threatening to throw it counts for their opponent.
+            let Some(queen_hex) = board.queen(player)
+            else
+            {
+                continue;
+            };
+
+            let liberties = hex::neighbours(queen_hex).iter().filter(|hex| !board.occupied(**hex)).count();
+            features[QUEEN_LIBERTIES] += sign * liberties as f64;
+
+            if board.top(queen_hex).is_some_and(|top| top.player != player)
+            {
+                features[QUEEN_COVERED] -= sign;
+            }
+
+            features[PILLBUG_THREATS] -= sign * Self::pillbug_threats(board, player, queen_hex) as f64;
+        }
+
+        features[TEMPO] = 1.0;
+        Features(features)
+    }
+
+    /// Counts the enemy Pillbugs, or Mosquitos next to a Pillbug, that could throw a player's queen.
+    fn pillbug_threats(board: &Board, player: Player, queen_hex: Hex) -> usize
+    {
+        let queen = Piece {
+            player,
+            kind: Bug::Queen,
+            num: 1,
+        };
+
+        if board.stacked(&queen) || board.is_pinned(&queen)
+        {
+            return 0;
+        }
+
+        board
+            .neighbours(queen_hex)
+            .iter()
+            .filter(|piece| piece.player != player)
+            .filter(|piece| match piece.kind
+            {
+                | Bug::Pillbug => true,
+                | Bug::Mosquito => board
+                    .location(piece)
+                    .is_some_and(|hex| board.neighbours(hex).iter().any(|n| n.kind == Bug::Pillbug)),
+                | _ => false,
+            })
+            .count()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// The weight of each feature in the linear evaluation.
+///
+/// Weights are read and written as a map from feature names to weights, so files only need to name the weights they
+/// change.
+pub struct LinearWeights(pub [f64; FEATURE_COUNT]);
+
+impl Default for LinearWeights
+{
+    fn default() -> Self
+    {
+        LinearWeights([
+            // Mobility, per destination.
+            1.0, 2.0, 1.0, 1.5, 1.5, 1.5, 4.0, 1.0, //
+            // Pinned pieces.
+            -5.0, -4.0, -2.0, -4.0, -5.0, -4.0, -8.0, -2.0, //
+            // Pieces in hand.
+            3.0, 3.0, 2.0, 3.0, 4.0, 3.0, -10.0, 1.0, //
+            // Queen liberties, covered queens, Pillbug threats and tempo.
+            25.0, 40.0, 15.0, 5.0,
+        ])
+    }
+}
+
+impl LinearWeights
+{
+    /// Scores a position from the perspective of the player to move.
+    pub fn evaluate(&self, board: &Board) -> i32
+    {
+        let is_white = if board.to_move() == Player::White { 1 } else { -1 };
+
+        match board.state()
+        {
+            | GameState::NotStarted | GameState::Draw => 0,
+            | GameState::WhiteWins => MINIMUM_WIN * is_white,
+            | GameState::BlackWins => -MINIMUM_WIN * is_white,
+            | _ =>
+            {
+                let Features(features) = Features::extract(board);
+                let score = self.0.iter().zip(features).map(|(w, f)| w * f).sum::<f64>();
+                (score.floor() as i32).clamp(-MINIMUM_WIN + 1, MINIMUM_WIN - 1)
+            }
+        }
+    }
+
+    /// Reads weights from a file, as TOML if it has a `.toml` extension and as JSON otherwise.
+    pub fn load(path: &Path) -> Result<LinearWeights>
+    {
+        let base = || Error::new(Kind::InvalidOption, format!("Could not load linear weights from '{}'.", path.display()));
+        let contents = std::fs::read_to_string(path).map_err(|err| Error::from(err).chain(base()))?;
+
+        let parsed = if path.extension().is_some_and(|ext| ext == "toml")
+        {
+            toml::from_str::<LinearWeights>(&contents).map_err(|err| err.to_string())
+        }
+        else
+        {
+            serde_json::from_str::<LinearWeights>(&contents).map_err(|err| err.to_string())
+        };
+
+        parsed.map_err(|msg| Error::new(Kind::ParseError, msg).chain(base()))
+    }
+}
+
+impl Serialize for LinearWeights
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    {
+        serializer.collect_map(Features::NAMES.iter().zip(self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for LinearWeights
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error>
+    {
+        let named = HashMap::<String, f64>::deserialize(deserializer)?;
+        let mut weights = LinearWeights::default();
+
+        for (name, weight) in named
+        {
+            let i = Features::NAMES
+                .iter()
+                .position(|n| *n == name)
+                .ok_or_else(|| serde::de::Error::unknown_field(&name, &Features::NAMES))?;
+            weights.0[i] = weight;
+        }
+
+        Ok(weights)
+    }
+}
+
+#[derive(Debug)]
+/// An evaluator that searches like [`StrongestEvaluator`], but scores positions with a weighted sum of [`Features`].
+pub struct LinearEvaluator
+{
+    search: StrongestEvaluator,
+}
+
+impl Evaluator for LinearEvaluator
+{
+    type Generator<'a> = super::PrioritizingMoveGenerator<'a>;
+
+    fn best_move(&mut self, board: &Board, args: SearchArgs) -> Move
+    {
+        self.search.best_move(board, args)
+    }
+
+    fn clear(&mut self)
+    {
+        self.search.clear();
+    }
+
+    fn depth(&self) -> Depth
+    {
+        self.search.depth()
+    }
+
+    fn generate_moves(board: &Board) -> Self::Generator<'_>
+    {
+        StrongestEvaluator::generate_moves(board)
+    }
+
+    fn load_table(&mut self, path: &Path) -> Result<usize>
+    {
+        self.search.load_table(path)
+    }
+
+    fn memory(&self) -> usize
+    {
+        self.search.memory()
+    }
+
+    fn new(options: UhpOptions) -> Self
+    {
+        LinearEvaluator {
+            search: StrongestEvaluator::with_leaf_evaluation(options, LeafEvaluation::Linear),
+        }
+    }
+
+    fn new_game(&mut self)
+    {
+        self.search.new_game();
+    }
+
+    fn nodes(&self) -> u64
+    {
+        self.search.nodes()
+    }
+
+    fn save_table(&self, path: &Path) -> Result<usize>
+    {
+        self.search.save_table(path)
+    }
+
+    fn set_options(&mut self, options: &UhpOptions)
+    {
+        self.search.set_options(options);
+    }
+}
@@ -0,0 +1,255 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Mutex,
+        PoisonError,
+    },
+    thread,
+    time::Instant,
+};
+
+use rand::thread_rng;
+
+use super::EvalParams;
+use crate::prelude::*;
+
+mod playout;
+mod tree;
+
+pub use playout::PlayoutPolicy;
+use playout::is_over;
+use tree::*;
+
+#[derive(Debug)]
+/// An evaluator based on Monte-Carlo tree search, which picks the moves to explore by PUCT and scores them by playing
+/// them out.
+///
+/// Threads share a single tree, and the tree is kept between searches so that each search picks up where the last one
+/// left off.
+pub struct MctsEvaluator
+{
+    options:   UhpOptions,
+    tree:      Option<Tree>,
+    playouts:  u64,
+    reused:    u64,
+    max_depth: usize,
+}
+
+/// The limits on a search, shared between its threads.
+struct Limits
+{
+    stopped:   AtomicBool,
+    started:   AtomicU64,
+    playouts:  u64,
+    capacity:  usize,
+    max_depth: AtomicUsize,
+}
+
+impl Evaluator for MctsEvaluator
+{
+    type Generator<'a> = super::BasicMoveGenerator;
+
+    fn best_move(&mut self, board: &Board, args: SearchArgs) -> Move
+    {
+        let moves = super::BasicMoveGenerator::new(board, true).collect::<Vec<Move>>();
+
+        if moves.len() == 1
+        {
+            moves[0]
+        }
+        else
+        {
+            self.search(board, args)
+        }
+    }
+
+    fn clear(&mut self)
+    {
+        self.tree = None;
+    }
+
+    fn depth(&self) -> Depth
+    {
+        Depth::from(self.max_depth.min(u8::MAX as usize) as u8)
+    }
+
+    fn generate_moves(board: &Board) -> Self::Generator<'_>
+    {
+        super::BasicMoveGenerator::new(board, false)
+    }
+
+    fn memory(&self) -> usize
+    {
+        self.tree.as_ref().map_or(0, |tree| tree.len() * std::mem::size_of::<Node>())
+    }
+
+    fn new(options: UhpOptions) -> Self
+    {
+        MctsEvaluator {
+            options,
+            tree:      None,
+            playouts:  0,
+            reused:    0,
+            max_depth: 0,
+        }
+    }
+
+    fn new_game(&mut self)
+    {
+        self.tree = None;
+    }
+
+    fn nodes(&self) -> u64
+    {
+        self.playouts
+    }
+
+    fn set_options(&mut self, options: &UhpOptions)
+    {
+        self.options = options.clone();
+    }
+}
+
+impl MctsEvaluator
+{
+    /// The playouts per ply when searching to a depth, which has no direct meaning for a tree that grows unevenly.
+    const PLAYOUTS_PER_PLY: u64 = 1000;
+
+    /// The most nodes that fit in the memory given to transpositions, which the tree stands in for.
+    fn capacity(&self) -> usize
+    {
+        ((self.options.table_memory * 1e+9) as usize / std::mem::size_of::<Node>()).max(1)
+    }
+
+    /// The playouts that the last search inherited from the tree of an earlier one, which [`Evaluator::nodes`] leaves
+    /// out.
+    pub fn reused(&self) -> u64
+    {
+        self.reused
+    }
+
+    /// The most playouts to run for the given search options.
+    fn playout_limit(args: SearchArgs) -> u64
+    {
+        match args
+        {
+            | SearchArgs::Depth(depth) => depth.floor().max(1) as u64 * Self::PLAYOUTS_PER_PLY,
+            | SearchArgs::Nodes(nodes) => nodes,
+            | SearchArgs::Time(_) => u64::MAX,
+        }
+    }
+
+    /// Searches a gamestate for the best continuation.
+    fn search(&mut self, board: &Board, args: SearchArgs) -> Move
+    {
+        let start = Instant::now();
+        let capacity = self.capacity();
+
+        let tree = self
+            .tree
+            .take()
+            .and_then(|mut tree| (tree.advance(board) && tree.len() <= capacity).then_some(tree))
+            .unwrap_or_else(|| Tree::new(board));
+
+        let reused = tree.root().visits;
+        let root = tree.board().clone();
+        let tree = Mutex::new(tree);
+
+        let limits = Limits {
+            stopped:   AtomicBool::new(false),
+            started:   AtomicU64::new(0),
+            playouts:  Self::playout_limit(args),
+            capacity,
+            max_depth: AtomicUsize::new(0),
+        };
+
+        thread::scope(|s| {
+            let (tree, root, limits) = (&tree, &root, &limits);
+            let (policy, params) = (self.options.playout, &self.options.eval);
+
+            // Our worker threads.
+            for _ in 0..self.options.num_threads.max(1)
+            {
+                s.spawn(move || Self::work(tree, root, limits, policy, params));
+            }
+
+            // Our timer thread.
+            if let SearchArgs::Time(duration) = args
+            {
+                s.spawn(move || {
+                    std::thread::sleep(duration);
+                    limits.stopped.store(true, Ordering::SeqCst);
+                });
+            }
+        });
+
+        let tree = tree.into_inner().unwrap_or_else(PoisonError::into_inner);
+        let best = tree.best_child();
+        let mv = best.map(|node| node.mv).unwrap_or_else(|| Self::generate_moves(board).next().unwrap_or(Move::Pass));
+
+        self.playouts = (tree.root().visits - reused) as u64;
+        self.reused = reused as u64;
+        self.max_depth = limits.max_depth.load(Ordering::Relaxed);
+
+        log::info!(
+            "found move '{}' with {} playouts ({} reused) in {:.1} s: win rate {:.1}%, depth {}, {} nodes",
+            mv,
+            self.playouts,
+            reused,
+            start.elapsed().as_secs_f64(),
+            best.map_or(50.0, |node| node.win_rate() * 100.0),
+            self.max_depth,
+            tree.len()
+        );
+
+        self.tree = Some(tree);
+        mv
+    }
+
+    /// Runs playouts until the search is stopped or runs out of playouts.
+    ///
+    /// The tree is only locked to pick a leaf, to add its children and to record the result, so threads spend most of
+    /// their time generating moves and playing out positions side by side.
+    fn work(tree: &Mutex<Tree>, root: &Board, limits: &Limits, policy: PlayoutPolicy, params: &EvalParams)
+    {
+        let lock = || tree.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut rng = thread_rng();
+
+        while !limits.stopped.load(Ordering::Relaxed)
+        {
+            if limits.started.fetch_add(1, Ordering::Relaxed) >= limits.playouts
+            {
+                limits.stopped.store(true, Ordering::SeqCst);
+                break;
+            }
+
+            let (path, moves, expand) = {
+                let mut tree = lock();
+                let path = tree.select();
+                let moves = path[1..].iter().map(|index| tree.node(*index).mv).collect::<Vec<Move>>();
+
+                // Leaves are only expanded once they have been played out, which keeps the tree from filling up with
+                // the children of moves that will never be looked at again.
+                let leaf = *path.last().unwrap();
+                let expand = leaf == Tree::ROOT || tree.node(leaf).visits > 0;
+                (path, moves, expand)
+            };
+
+            limits.max_depth.fetch_max(moves.len(), Ordering::Relaxed);
+
+            let mut board = root.clone();
+            moves.iter().for_each(|mv| {
+                board.play_unchecked(mv);
+            });
+
+            if expand && !is_over(&board)
+            {
+                let children = Node::expansion(&board, policy);
+                lock().expand(*path.last().unwrap(), children, limits.capacity);
+            }
+
+            let result = policy.playout(params, &mut board, &mut rng);
+            lock().backpropagate(&path, result);
+        }
+    }
+}
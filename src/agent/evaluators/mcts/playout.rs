@@ -0,0 +1,114 @@
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
+
+use crate::{agent::arena::MAX_PLIES, prelude::*};
+
+use crate::agent::evaluators::{EvalParams, StrongestEvaluator};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+/// How the Monte-Carlo evaluator picks moves when it plays out a position, which also sets the priors of new nodes.
+pub enum PlayoutPolicy
+{
+    /// Every move is equally likely.
+    Random,
+    #[default]
+    /// Moves that bring a bug next to the enemy queen are much more likely than the rest.
+    Queen,
+}
+
+impl PlayoutPolicy
+{
+    /// How much more likely a move that attacks the enemy queen is than any other move.
+    const QUEEN_ATTACK_WEIGHT: f64 = 8.0;
+
+    /// The most moves played out from a leaf before the position is scored by the static evaluation instead.
+    const PLAYOUT_PLIES: usize = 24;
+
+    /// How many centipawns of static evaluation make a win about 73% likely, when scoring unfinished playouts.
+    const EVAL_SCALE: f64 = 200.0;
+
+    /// The legal moves in a position with their weights under this policy, or a lone Pass if there are none.
+    pub fn weighted_moves(&self, board: &Board) -> Vec<(Move, f64)>
+    {
+        let mut moves = board.generate_moves(true);
+        if moves.is_empty()
+        {
+            moves.push(Move::Pass);
+        }
+
+        let target = match self
+        {
+            | PlayoutPolicy::Random => None,
+            | PlayoutPolicy::Queen => board.queen(board.to_move().flip()),
+        };
+
+        let Some(queen_hex) = target
+        else
+        {
+            return moves.into_iter().map(|mv| (mv, 1.0)).collect();
+        };
+
+        let around = hex::neighbours(queen_hex);
+        let attacks = |mv: &Move| {
+            let Some(to) = board.destination(mv)
+            else
+            {
+                return false;
+            };
+
+            // Shuffling around the queen does not attack it any more than it already is.
+            let from = match mv
+            {
+                | Move::Move(piece, _) => board.location(piece),
+                | _ => None,
+            };
+
+            (to == queen_hex || around.contains(&to)) && !from.is_some_and(|from| around.contains(&from))
+        };
+
+        moves
+            .into_iter()
+            .map(|mv| {
+                let weight = if attacks(&mv) { Self::QUEEN_ATTACK_WEIGHT } else { 1.0 };
+                (mv, weight)
+            })
+            .collect()
+    }
+
+    /// Plays out a position, returning the chance that the player to move wins it, from 0 to 1.
+    ///
+    /// Playouts are cut short after a few moves and scored by the static evaluation, because random games of Hive
+    /// rarely end.
+    pub fn playout<R: Rng>(&self, params: &EvalParams, board: &mut Board, rng: &mut R) -> f64
+    {
+        let player = board.to_move();
+
+        for _ in 0..Self::PLAYOUT_PLIES
+        {
+            if is_over(board)
+            {
+                break;
+            }
+
+            let moves = self.weighted_moves(board);
+            let index = match WeightedIndex::new(moves.iter().map(|(_, weight)| *weight))
+            {
+                | Ok(dist) => dist.sample(rng),
+                | Err(_) => rng.gen_range(0..moves.len()),
+            };
+
+            board.play_unchecked(&moves[index].0);
+        }
+
+        let score = StrongestEvaluator::evaluate_board(params, board) as f64;
+        let score = if board.to_move() == player { score } else { -score };
+
+        1.0 / (1.0 + (-score / Self::EVAL_SCALE).exp())
+    }
+}
+
+/// Whether a game has ended, or gone on too long to play out any further.
+pub(super) fn is_over(board: &Board) -> bool
+{
+    let finished = matches!(board.state(), GameState::Draw | GameState::WhiteWins | GameState::BlackWins);
+    finished || board.turn() as usize >= MAX_PLIES
+}
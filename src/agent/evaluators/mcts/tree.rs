@@ -0,0 +1,267 @@
+use std::ops::Range;
+
+use super::PlayoutPolicy;
+use crate::prelude::*;
+
+#[derive(Clone, Debug)]
+/// A position in the search tree, reached by playing a move from its parent.
+pub struct Node
+{
+    pub mv:           Move,
+    pub hash:         ZobristHash,
+    /// The policy's weight for the move, as a fraction of the weights of all of its siblings.
+    pub prior:        f64,
+    pub visits:       u32,
+    /// The sum of the results of the playouts through this node, for the player who made its move.
+    pub value:        f64,
+    /// Playouts in flight through this node, which count as losses until they finish so that other threads look
+    /// elsewhere.
+    pub virtual_loss: u32,
+    pub children:     Option<Range<usize>>,
+}
+
+impl Node
+{
+    /// Creates an unvisited node.
+    pub fn new(mv: Move, hash: ZobristHash, prior: f64) -> Node
+    {
+        Node {
+            mv,
+            hash,
+            prior,
+            visits:       0,
+            value:        0.0,
+            virtual_loss: 0,
+            children:     None,
+        }
+    }
+
+    /// The children of a position, one for each move the policy could play, with the policy's weights as priors.
+    pub fn expansion(board: &Board, policy: PlayoutPolicy) -> Vec<Node>
+    {
+        let moves = policy.weighted_moves(board);
+        let mut children = Vec::<Node>::with_capacity(moves.len());
+
+        let mut board = board.clone();
+        for (mv, weight) in moves
+        {
+            let hash = board.play_unchecked(&mv);
+            board.undo_one().expect("could not undo a move that was just played");
+
+            // A Mosquito can reach the same hex by copying different bugs, so the same move may be generated twice. Only
+            // one child is kept for it, as the tree finds children by their hash when it is reused.
+            if !children.iter().any(|child| child.hash == hash)
+            {
+                children.push(Node::new(mv, hash, weight));
+            }
+        }
+
+        let total = children.iter().map(|child| child.prior).sum::<f64>();
+        for child in &mut children
+        {
+            child.prior /= total;
+        }
+
+        children
+    }
+
+    /// The fraction of the playouts through this node won by the player who made its move.
+    pub fn win_rate(&self) -> f64
+    {
+        self.value / self.visits.max(1) as f64
+    }
+}
+
+#[derive(Clone, Debug)]
+/// A Monte-Carlo search tree, stored as a flat list of nodes where the children of each node are contiguous.
+pub struct Tree
+{
+    nodes: Vec<Node>,
+    board: Board,
+}
+
+impl Tree
+{
+    /// The index of the root node.
+    pub const ROOT: usize = 0;
+
+    /// How strongly the priors and the uncertainty of rarely visited nodes pull the search towards them.
+    const EXPLORATION: f64 = 1.5;
+
+    /// The value given to nodes that have never been visited, which is as good as a draw.
+    const FIRST_PLAY_URGENCY: f64 = 0.5;
+
+    /// The most moves that can have been played since the last search for its tree to be reused.
+    const REUSE_PLIES: usize = 4;
+
+    /// Creates a tree with only a root, for the given position.
+    pub fn new(board: &Board) -> Tree
+    {
+        Tree {
+            nodes: vec![Node::new(Move::Pass, board.zobrist(), 1.0)],
+            board: board.clone(),
+        }
+    }
+
+    /// Moves the root of the tree forward to the given position, keeping everything learned about it, if the position
+    /// follows from the root within a few moves and the tree has seen those moves. Returns whether it did.
+    pub fn advance(&mut self, board: &Board) -> bool
+    {
+        let mut ancestor = board.clone();
+        let mut hashes = vec![board.zobrist()];
+
+        for _ in 0..=Self::REUSE_PLIES
+        {
+            if ancestor.turn() == self.board.turn() && ancestor.zobrist() == self.root().hash
+            {
+                let mut index = Self::ROOT;
+                for hash in hashes.iter().rev().skip(1)
+                {
+                    let Some(child) = self.children(index).iter().position(|node| node.hash == *hash)
+                    else
+                    {
+                        return false;
+                    };
+                    index = self.nodes[index].children.as_ref().unwrap().start + child;
+                }
+
+                self.rebase(index);
+                self.board = board.clone();
+                return true;
+            }
+
+            if ancestor.turn() <= self.board.turn() || ancestor.undo_one().is_err()
+            {
+                return false;
+            }
+            hashes.push(ancestor.zobrist());
+        }
+
+        false
+    }
+
+    /// Records the result of a playout along the path it took, removing the virtual loss that [`Tree::select`] added.
+    /// The result is from the perspective of the player to move at the end of the path.
+    pub fn backpropagate(&mut self, path: &[usize], result: f64)
+    {
+        // Each node is scored for the player who made its move, which is the opponent of the player to move after it.
+        let mut result = 1.0 - result;
+
+        for index in path.iter().rev()
+        {
+            let node = &mut self.nodes[*index];
+            node.virtual_loss -= 1;
+            node.visits += 1;
+            node.value += result;
+            result = 1.0 - result;
+        }
+    }
+
+    /// The most visited move at the root, if the root has been expanded.
+    pub fn best_child(&self) -> Option<&Node>
+    {
+        self.children(Self::ROOT).iter().max_by_key(|node| node.visits)
+    }
+
+    /// The position at the root.
+    pub fn board(&self) -> &Board
+    {
+        &self.board
+    }
+
+    /// The children of a node, which are empty until it is expanded.
+    pub fn children(&self, index: usize) -> &[Node]
+    {
+        match &self.nodes[index].children
+        {
+            | Some(range) => &self.nodes[range.clone()],
+            | None => &[],
+        }
+    }
+
+    /// Adds children to a leaf, unless another thread got there first or there is no room for them.
+    pub fn expand(&mut self, leaf: usize, children: Vec<Node>, capacity: usize)
+    {
+        if self.nodes[leaf].children.is_some() || self.nodes.len() + children.len() > capacity
+        {
+            return;
+        }
+
+        let start = self.nodes.len();
+        self.nodes.extend(children);
+        self.nodes[leaf].children = Some(start..self.nodes.len());
+    }
+
+    /// The number of nodes in the tree.
+    pub fn len(&self) -> usize
+    {
+        self.nodes.len()
+    }
+
+    /// Gets a node by its index.
+    pub fn node(&self, index: usize) -> &Node
+    {
+        &self.nodes[index]
+    }
+
+    /// The root node.
+    pub fn root(&self) -> &Node
+    {
+        &self.nodes[Self::ROOT]
+    }
+
+    /// Walks from the root to a leaf, at each step taking the child with the best PUCT score, and adds a virtual loss
+    /// to every node on the way. Returns the indices of the nodes on the path, starting from the root.
+    pub fn select(&mut self) -> Vec<usize>
+    {
+        let mut index = Self::ROOT;
+        let mut path = vec![index];
+        self.nodes[index].virtual_loss += 1;
+
+        while let Some(children) = self.nodes[index].children.clone()
+        {
+            let parent = &self.nodes[index];
+            let explore = Self::EXPLORATION * ((parent.visits + parent.virtual_loss) as f64).sqrt();
+
+            index = children.max_by(|a, b| self.puct(*a, explore).total_cmp(&self.puct(*b, explore))).unwrap();
+            self.nodes[index].virtual_loss += 1;
+            path.push(index);
+        }
+
+        path
+    }
+}
+
+impl Tree
+{
+    /// Scores a node for selection, as its value plus a bonus for its prior that shrinks as it is visited.
+    fn puct(&self, index: usize, explore: f64) -> f64
+    {
+        let node = &self.nodes[index];
+        let visits = node.visits + node.virtual_loss;
+
+        let value = if visits == 0 { Self::FIRST_PLAY_URGENCY } else { node.value / visits as f64 };
+        value + explore * node.prior / (1 + visits) as f64
+    }
+
+    /// Makes a node the new root, dropping everything outside of its subtree.
+    fn rebase(&mut self, index: usize)
+    {
+        let mut nodes = vec![self.nodes[index].clone()];
+
+        // Copying children in the order their parents are copied keeps them contiguous.
+        let mut i = 0;
+        while i < nodes.len()
+        {
+            if let Some(range) = nodes[i].children.clone()
+            {
+                let start = nodes.len();
+                nodes.extend_from_slice(&self.nodes[range]);
+                nodes[i].children = Some(start..nodes.len());
+            }
+            i += 1;
+        }
+
+        self.nodes = nodes;
+    }
+}
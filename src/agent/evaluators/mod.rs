@@ -1,5 +1,14 @@
 pub mod basic;
 pub use basic::{BasicEvaluator as Basic, *};
 
+pub mod dynamic;
+pub use dynamic::{DynamicEvaluator as Dynamic, *};
+
+pub mod linear;
+pub use linear::{LinearEvaluator as Linear, *};
+
+pub mod mcts;
+pub use mcts::{MctsEvaluator as Mcts, *};
+
 pub mod strongest;
 pub use strongest::{StrongestEvaluator as Strongest, *};
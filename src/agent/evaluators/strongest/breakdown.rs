@@ -0,0 +1,243 @@
+use serde::Serialize;
+
+use super::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// How a bug on top of the Hive counts towards its owner's material, before weighting.
+pub struct PieceMaterial
+{
+    /// The value of the bug, or of the bug a Mosquito copies.
+    pub value:     f64,
+    /// The bug a Mosquito was rescored as, if any.
+    pub copies:    Option<Bug>,
+    pub pinned:    bool,
+    pub stacked:   bool,
+    /// Whether the bug is next to the enemy queen, where it is worth nothing so that cheap bugs attack first.
+    pub attacking: bool,
+}
+
+impl PieceMaterial
+{
+    /// The bug's contribution to material, from its owner's perspective.
+    pub fn score(&self, params: &EvalParams) -> f64
+    {
+        if self.pinned || self.attacking
+        {
+            0.0
+        }
+        else if self.stacked
+        {
+            // Heavily reweight a bug that is on a stack, because it is pinning something underneath!
+            self.value * params.k_stacking
+        }
+        else
+        {
+            self.value
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// How freely a bug on top of the Hive can act, before weighting.
+pub struct PieceMobility
+{
+    /// The distinct hexes the bug could move to, including by moves a Mosquito borrows.
+    pub destinations: usize,
+    /// The throws the bug could make as a Pillbug.
+    pub throws:       usize,
+}
+
+impl PieceMobility
+{
+    /// The bug's contribution to mobility, from its owner's perspective.
+    pub fn score(&self) -> f64
+    {
+        ((self.destinations + self.throws) as f64).sqrt()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+/// The safety of one player's queen, term by term, before weighting.
+pub struct QueenTerms
+{
+    /// Penalties for the bugs filling the queen's killspots.
+    pub killspots:    f64,
+    /// Bonuses for friendly Pillbugs, or Mosquitos next to them, that can throw the queen to safety.
+    pub escapes:      f64,
+    /// Penalties for enemy Pillbugs, or Mosquitos next to them, that can throw the queen somewhere worse.
+    pub threats:      f64,
+    /// A bonus for being able to drop an unplaced Pillbug next to the queen.
+    pub pillbug_drop: f64,
+}
+
+impl QueenTerms
+{
+    /// The sum of the terms.
+    pub fn total(&self) -> f64
+    {
+        self.killspots + self.escapes + self.threats + self.pillbug_drop
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+/// How one bug on top of the Hive scored.
+pub struct PieceTerm
+{
+    pub piece:        String,
+    pub value:        f64,
+    pub copies:       Option<String>,
+    pub pinned:       bool,
+    pub stacked:      bool,
+    pub attacking:    bool,
+    /// The weighted contribution to material, from its owner's perspective.
+    pub score:        f64,
+    pub destinations: usize,
+    pub throws:       usize,
+    /// The weighted contribution to mobility, from its owner's perspective.
+    pub mobility:     f64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+/// One player's terms of the static evaluation, weighted, from that player's perspective.
+pub struct PlayerBreakdown
+{
+    pub material: f64,
+    pub mobility: f64,
+    pub pieces:   Vec<PieceTerm>,
+    pub queens:   f64,
+    /// The queen terms before they are weighted.
+    pub queen:    QueenTerms,
+    /// The weighted tempi the opponent needs to surround the player's queen.
+    pub race:     f64,
+    pub reserve:  f64,
+}
+
+impl PlayerBreakdown
+{
+    /// The sum of the player's weighted terms.
+    pub fn total(&self) -> f64
+    {
+        self.material + self.mobility + self.queens + self.race + self.reserve
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+/// Every term of the static evaluation of a position, for both players.
+pub struct EvalBreakdown
+{
+    pub to_move: String,
+    /// How far the game has come, which the weights were tapered by.
+    pub phase:   f64,
+    pub white:   PlayerBreakdown,
+    pub black:   PlayerBreakdown,
+    /// The score the static evaluation gives the position, from the perspective of the player to move.
+    pub score:   i32,
+}
+
+impl StrongestEvaluator
+{
+    /// Breaks the static evaluation of a position down into its terms.
+    ///
+    /// The score is the evaluation itself, so for a game in progress it is the difference between the players' totals
+    /// rounded down, and for a finished game it is a win, loss or draw.
+    pub fn breakdown(params: &EvalParams, board: &Board) -> EvalBreakdown
+    {
+        let phase = Self::phase(board);
+        let tapered = params.tapered(phase);
+        let reach = Reach::new(board);
+
+        EvalBreakdown {
+            to_move: board.to_move().to_string(),
+            phase,
+            white:   Self::player_breakdown(&tapered, board, &reach, Player::White),
+            black:   Self::player_breakdown(&tapered, board, &reach, Player::Black),
+            score:   Self::evaluate_board(params, board),
+        }
+    }
+}
+
+impl StrongestEvaluator
+{
+    /// Collects one player's terms of the static evaluation.
+    fn player_breakdown(params: &EvalParams, board: &Board, reach: &Reach, player: Player) -> PlayerBreakdown
+    {
+        let mut pieces = reach
+            .bugs()
+            .filter(|(piece, ..)| piece.player == player)
+            .map(|(piece, hex, destinations)| {
+                let material = Self::piece_material(params, board, hex, piece);
+                let mobility = Self::piece_mobility(board, &piece, destinations);
+                PieceTerm {
+                    piece:        piece.to_string(),
+                    value:        material.value,
+                    copies:       material.copies.map(|bug| bug.long().to_string()),
+                    pinned:       material.pinned,
+                    stacked:      material.stacked,
+                    attacking:    material.attacking,
+                    score:        params.k_moveable * material.score(params),
+                    destinations: mobility.destinations,
+                    throws:       mobility.throws,
+                    mobility:     params.k_mobility * mobility.score(),
+                }
+            })
+            .collect::<Vec<_>>();
+        pieces.sort_by(|a, b| a.piece.cmp(&b.piece));
+
+        let queen = Self::queen_terms(params, board, player);
+
+        PlayerBreakdown {
+            material: pieces.iter().map(|p| p.score).sum(),
+            mobility: pieces.iter().map(|p| p.mobility).sum(),
+            pieces,
+            queens:   params.k_queens * queen.total(),
+            queen,
+            race:     params.k_race * Self::race_for(board, reach, player),
+            reserve:  params.k_reserve * Self::reserve_for(params, board, player),
+        }
+    }
+}
+
+impl std::fmt::Display for EvalBreakdown
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        let (w, b) = (&self.white, &self.black);
+        let row = |f: &mut std::fmt::Formatter<'_>, name: &str, white: f64, black: f64| {
+            writeln!(f, "{name: <18}{white: >10.2}{black: >10.2}")
+        };
+
+        writeln!(f, "{: <18}{: >10}{: >10}", "term", "White", "Black")?;
+        row(f, "material", w.material, b.material)?;
+        row(f, "mobility", w.mobility, b.mobility)?;
+        row(f, "queens", w.queens, b.queens)?;
+        row(f, "  killspots", w.queen.killspots, b.queen.killspots)?;
+        row(f, "  pillbug escapes", w.queen.escapes, b.queen.escapes)?;
+        row(f, "  pillbug threats", w.queen.threats, b.queen.threats)?;
+        row(f, "  pillbug drop", w.queen.pillbug_drop, b.queen.pillbug_drop)?;
+        row(f, "race", w.race, b.race)?;
+        row(f, "reserve", w.reserve, b.reserve)?;
+        row(f, "total", w.total(), b.total())?;
+
+        writeln!(f)?;
+        writeln!(f, "{: <6}{: >8}  {: <24}{: >10}{: >8}{: >8}{: >10}", "piece", "value", "notes", "score", "moves", "throws", "mobility")?;
+        for piece in w.pieces.iter().chain(b.pieces.iter())
+        {
+            let notes = [
+                piece.copies.as_ref().map(|bug| format!("as {}", bug)),
+                piece.pinned.then(|| "pinned".to_string()),
+                piece.stacked.then(|| "stacked".to_string()),
+                piece.attacking.then(|| "attacking".to_string()),
+            ];
+            let notes = notes.into_iter().flatten().collect::<Vec<_>>().join(", ");
+
+            writeln!(
+                f,
+                "{: <6}{: >8.2}  {: <24}{: >10.2}{: >8}{: >8}{: >10.2}",
+                piece.piece, piece.value, notes, piece.score, piece.destinations, piece.throws, piece.mobility
+            )?;
+        }
+
+        writeln!(f)?;
+        write!(f, "score {} for {} to move, phase {:.2}", self.score, self.to_move, self.phase)
+    }
+}
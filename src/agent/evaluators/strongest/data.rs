@@ -3,20 +3,22 @@ use std::{
     time::Instant,
 };
 
-use mini_moka::sync::Cache;
-
+use super::{CountermoveTable, HistoryTable, IterationStats, KillerTable, LeafEvaluation, SearchStats};
 use crate::prelude::*;
 
 #[derive(Debug)]
 /// Contains any information shared between threads.
 pub struct GlobalData
 {
-    pub args:           SearchArgs,
-    pub max_depth:      AtomicU64,
-    pub options:        UhpOptions,
-    pub start_time:     Instant,
-    pub stopped:        AtomicBool,
-    pub transpositions: TranspositionTable,
+    pub args:            SearchArgs,
+    pub cache:           PositionCache,
+    pub leaf_evaluation: LeafEvaluation,
+    pub max_depth:       AtomicU64,
+    pub options:         UhpOptions,
+    pub start_time:      Instant,
+    pub stopped:         AtomicBool,
+    pub telemetry:       Telemetry,
+    pub transpositions:  TranspositionTable,
 }
 
 impl GlobalData
@@ -24,16 +26,20 @@ impl GlobalData
     /// Creates a new GlobalData with the given options.
     pub fn new(options: &UhpOptions) -> GlobalData
     {
-        let table_bytes = (options.table_memory * 1e+9) as usize;
-        let table = TranspositionTable::new(table_bytes);
+        let table = TranspositionTable::new(Self::bytes(options.table_memory));
+        let cache = PositionCache::new(Self::bytes(options.cache_memory));
+        let telemetry = Self::open_telemetry(options);
 
         GlobalData {
-            args:           SearchArgs::Depth(Depth::new(0)),
-            max_depth:      AtomicU64::new(0),
-            options:        options.clone(),
-            start_time:     Instant::now(),
-            stopped:        AtomicBool::new(false),
-            transpositions: table,
+            args:            SearchArgs::Depth(Depth::new(0)),
+            cache,
+            leaf_evaluation: LeafEvaluation::default(),
+            max_depth:       AtomicU64::new(0),
+            options:         options.clone(),
+            start_time:      Instant::now(),
+            stopped:         AtomicBool::new(false),
+            telemetry,
+            transpositions:  table,
         }
     }
 
@@ -46,6 +52,48 @@ impl GlobalData
         self.start_time = Instant::now();
         self.stopped.store(false, Ordering::SeqCst);
         self.transpositions.increment();
+        self.cache.reset_counters();
+    }
+
+    /// The deepest the search may go, given the search args and the skill level.
+    pub fn depth_limit(&self) -> Depth
+    {
+        self.args.depth().min(self.options.skill().depth())
+    }
+
+    /// Forgets every transposition and cached position.
+    pub fn clear(&self)
+    {
+        self.transpositions.clear();
+        self.cache.clear();
+    }
+
+    /// Reallocates the table and cache if the options now ask for a different amount of memory, which empties them, and
+    /// empties them if the evaluation weights changed.
+    pub fn resize(&mut self, options: &UhpOptions)
+    {
+        if options.table_memory != self.options.table_memory
+        {
+            self.transpositions = TranspositionTable::new(Self::bytes(options.table_memory));
+        }
+
+        if options.cache_memory != self.options.cache_memory
+        {
+            self.cache = PositionCache::new(Self::bytes(options.cache_memory));
+        }
+
+        // Every cached evaluation and search result was scored with the old weights.
+        if options.eval != self.options.eval || options.linear != self.options.linear
+        {
+            self.clear();
+        }
+
+        if options.telemetry.as_deref() != self.telemetry.path()
+        {
+            self.telemetry = Self::open_telemetry(options);
+        }
+
+        self.options = options.clone();
     }
 
     /// Determines if the search should end. If so, it sets the stopped flag as well.
@@ -59,56 +107,113 @@ impl GlobalData
     {
         self.stopped.store(true, Ordering::SeqCst);
     }
+
+    /// Converts a memory option in gigabytes to bytes.
+    fn bytes(gigabytes: f64) -> usize
+    {
+        (gigabytes * 1e+9) as usize
+    }
+
+    /// Opens the telemetry file named by the options. Telemetry is optional, so failing to open it only disables it.
+    fn open_telemetry(options: &UhpOptions) -> Telemetry
+    {
+        Telemetry::open(options.telemetry.as_deref()).unwrap_or_else(|err| {
+            log::warn!("{}", err);
+            Telemetry::default()
+        })
+    }
 }
 
 #[derive(Clone, Debug)]
 /// Contains the thread data per thread, and a view into the global data.
 pub struct ThreadData
 {
-    pub id:         usize,
-    pub board:      Board,
-    pub variation:  Variation,
-    pub target:     i32,
-    pub leaf_count: u64,
-    pub stem_count: u64,
-    pub best_move:  Option<Move>,
-    pub cache:      Cache<(ZobristHash, Move), Board>,
+    pub id:          usize,
+    pub board:       Board,
+    pub variation:   Variation,
+    pub target:      i32,
+    /// The first aspiration window of the iteration in progress, if it did not search the full window.
+    pub window:      Option<[i32; 2]>,
+    pub stats:       SearchStats,
+    pub iterations:  Vec<IterationStats>,
+    pub best_move:   Option<Move>,
+    pub completed:   Depth,
+    pub history:     HistoryTable,
+    pub killers:     KillerTable,
+    pub counters:    CountermoveTable,
+    pub root_turn:   u8,
+    pub root_moves:  Vec<ScoredMove>,
+    /// The nodes this thread may search before it ends the search for everyone.
+    pub node_budget: u64,
 }
 
 impl ThreadData
 {
     /// Creates a new thread data instance.
-    pub fn new(options: UhpOptions, board: &Board) -> ThreadData
+    pub fn new(board: &Board) -> ThreadData
     {
-        let entry_size = std::mem::size_of::<Board>();
-        let threads: usize = std::thread::available_parallelism().map(|nzu| nzu.into()).unwrap_or(0);
-        let cap = options.cache_memory * 1e+9 / (threads as f64) / (entry_size as f64);
-
         ThreadData {
-            id:         0,
-            board:      board.clone(),
-            variation:  Variation::default(),
-            target:     0,
-            leaf_count: 0,
-            stem_count: 0,
-            best_move:  None,
-            cache:      Cache::new(cap.floor() as u64),
+            id:          0,
+            board:       board.clone(),
+            variation:   Variation::default(),
+            target:      0,
+            window:      None,
+            stats:       SearchStats::default(),
+            iterations:  Vec::new(),
+            best_move:   None,
+            completed:   Depth::NIL,
+            history:     HistoryTable::default(),
+            killers:     KillerTable::default(),
+            counters:    CountermoveTable::default(),
+            root_turn:   board.turn(),
+            root_moves:  Vec::new(),
+            node_budget: u64::MAX,
         }
     }
 
-    /// Plays a move but leverages the cache.
+    /// Plays a move on this thread's board.
     pub fn play(&mut self, mv: &Move)
     {
         self.board.play_unchecked(mv);
     }
 
-    /// Sets up the thread data for the upcoming search.
-    pub fn prepare(&mut self)
+    /// Takes back the last move played on this thread's board.
+    pub fn undo(&mut self)
     {
+        self.board.undo_one().expect("undid a move the search never played");
+    }
+
+    /// Forgets everything learned in earlier searches, for when the next position has nothing to do with them.
+    pub fn clear(&mut self)
+    {
+        self.history.clear();
+        self.killers.clear();
+        self.counters.clear();
+    }
+
+    /// Sets up the thread data for the upcoming search from the given position.
+    ///
+    /// History and countermoves carry over from earlier searches, but killers are indexed by the distance from the
+    /// root, so they do not.
+    pub fn prepare(&mut self, board: &Board, node_budget: u64)
+    {
+        self.board = board.clone();
+        self.node_budget = node_budget;
+        self.root_turn = board.turn();
+        self.killers.clear();
         self.variation = Variation::default();
         self.target = 0;
-        self.leaf_count = 0;
-        self.stem_count = 0;
+        self.window = None;
+        self.stats = SearchStats::default();
+        self.iterations.clear();
         self.best_move = None;
+        self.root_moves.clear();
+        self.completed = Depth::NIL;
+    }
+
+    /// The number of plies between the root of the search and the current board.
+    pub fn ply(&self) -> usize
+    {
+        self.board.turn().wrapping_sub(self.root_turn) as usize
     }
 }
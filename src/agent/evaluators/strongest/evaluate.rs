@@ -2,48 +2,46 @@ use std::collections::HashSet;
 
 use super::*;
 
-const ATTACKING_KILLSPOT: f64 = 1.2;
 const MINIMUM_OPEN_KILLSPOTS: usize = 2;
+const MAXIMUM_RACE_TEMPI: usize = 3;
 
-const K_DEFENSE: f64 = 40.0;
-const K_MOVEABLE: f64 = 2.0;
-const K_QUEEN_NEIGHBOURHOOD: f64 = 30.0;
-const K_QUEENS: f64 = 1.0;
-const K_RESERVE: f64 = 1.0;
-const K_STACKING: f64 = 2.0;
-
-const VALUE_ANT: f64 = 7.0;
-const VALUE_BEETLE: f64 = 6.0;
-const VALUE_GRASSHOPPER: f64 = 3.0;
-const VALUE_LADYBUG: f64 = 6.0;
-const VALUE_MOSQUITO: f64 = 8.0;
-const VALUE_PILLBUG: f64 = 6.0;
-const VALUE_QUEEN: f64 = 12.0;
-const VALUE_SPIDER: f64 = 2.0;
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// The static evaluation that the search scores positions with.
+pub enum LeafEvaluation
+{
+    #[default]
+    /// The hand-written heuristics, weighted by [`EvalParams`].
+    Heuristic,
+    /// A weighted sum of [`Features`](crate::agent::evaluators::Features), weighted by
+    /// [`LinearWeights`](crate::agent::evaluators::LinearWeights).
+    Linear,
+}
 
 impl StrongestEvaluator
 {
-    /// Gives a baseline value for a piece. The queen value is HIGH, because it refers to moveable queens.
-    ///
-    /// Moveable queens are strong because they can totally neutralize an opponent's tempo by escaping an attack.
-    fn bug_value(bug: Bug) -> f64
+    /// Returns the static evaluation of the board, going through the shared cache.
+    pub(super) fn static_eval(global_data: &GlobalData, board: &Board) -> i32
     {
-        match bug
+        global_data.cache.eval(board, |board| Self::evaluate(global_data, board))
+    }
+
+    /// Returns the static evaluation of the board with whichever evaluation the search was set up with.
+    pub(super) fn evaluate(global_data: &GlobalData, board: &Board) -> i32
+    {
+        match global_data.leaf_evaluation
         {
-            | Bug::Ant => VALUE_ANT,
-            | Bug::Beetle => VALUE_BEETLE,
-            | Bug::Grasshopper => VALUE_GRASSHOPPER,
-            | Bug::Ladybug => VALUE_LADYBUG,
-            | Bug::Mosquito => VALUE_MOSQUITO,
-            | Bug::Pillbug => VALUE_PILLBUG,
-            | Bug::Queen => VALUE_QUEEN,
-            | Bug::Spider => VALUE_SPIDER,
+            | LeafEvaluation::Heuristic => Self::evaluate_board(&global_data.options.eval, board),
+            | LeafEvaluation::Linear => global_data.options.linear.evaluate(board),
         }
     }
 
     /// Returns a score for the board in the moving player's perspective using some heuristics.
-    pub(super) fn evaluate_board(board: &Board) -> i32
+    ///
+    /// The weights are tapered between their opening and endgame values by the [phase](Self::phase) of the game.
+    pub fn evaluate_board(params: &EvalParams, board: &Board) -> i32
     {
+        let params = &params.tapered(Self::phase(board));
+
         let is_white = if board.to_move() == Player::White { 1 } else { -1 };
         let is_black = -is_white;
 
@@ -54,15 +52,46 @@ impl StrongestEvaluator
             | GameState::BlackWins => MINIMUM_WIN * is_black,
             | _ =>
             {
-                let score = Self::material(board) + Self::queens(board) + Self::reserve(board);
+                let reach = Reach::new(board);
+                let score = Self::material(params, board)
+                    + Self::mobility(params, board, &reach)
+                    + Self::queens(params, board)
+                    + Self::race(params, board, &reach)
+                    + Self::reserve(params, board);
                 let integer_score = score.floor() as i32;
                 integer_score.clamp(-MINIMUM_WIN + 1, MINIMUM_WIN - 1)
             }
         }
     }
 
+    /// Estimates how far the game has come, from 0 in the opening to 1 in the endgame.
+    ///
+    /// This is the mean of how much of both hands has been placed and how surrounded the more threatened queen is. Both are
+    /// read from the position alone, which the shared cache keys evaluations by, so the turn number is left out.
+    pub fn phase(board: &Board) -> f64
+    {
+        let pouch = board.pouch();
+        let total = pouch.extents().iter().map(|n| 2 * *n as usize).sum::<usize>();
+        let in_hand = [Player::White, Player::Black]
+            .into_iter()
+            .flat_map(|player| pouch.hand(player).iter())
+            .map(|n| *n as usize)
+            .sum::<usize>();
+        let development = 1.0 - in_hand as f64 / total.max(1) as f64;
+
+        let pressure = [Player::White, Player::Black]
+            .into_iter()
+            .filter_map(|player| board.queen(player))
+            .map(|queen| board.field().neighbours(queen).len())
+            .max()
+            .unwrap_or(0) as f64
+            / 6.0;
+
+        (development + pressure) / 2.0
+    }
+
     /// Returns the material advantage in the moving player's perspective, which is roughly the difference in board strength.
-    fn material(board: &Board) -> f64
+    fn material(params: &EvalParams, board: &Board) -> f64
     {
         let mut score = 0.0;
         let occupied: HashSet<Hex> = board.field().clone().into();
@@ -70,203 +99,358 @@ impl StrongestEvaluator
         for hex in occupied
         {
             let piece = board.top(hex).unwrap();
+            let piece_score = Self::piece_material(params, board, hex, piece).score(params);
 
-            // Discard pinned bugs.
-            if board.is_pinned(&piece)
-            {
-                continue;
-            }
+            // Invert it if it does not belong to the current player.
+            score += if piece.player == board.to_move() { piece_score } else { -piece_score };
+        }
 
-            let mut piece_score = Self::bug_value(piece.kind);
-            let stacking = board.stacked(&piece);
+        params.k_moveable * score
+    }
 
-            // Special check to rescore a mosquito as its best possible neighbour.
-            'mosquito_rescore: {
-                if piece.kind == Bug::Mosquito
-                {
-                    if stacking
-                    {
-                        piece_score = Self::bug_value(Bug::Beetle);
-                        break 'mosquito_rescore;
-                    }
-                    for neighbour in board.pieces_neighbouring(hex)
-                    {
-                        if neighbour.kind == Bug::Queen
-                        {
-                            continue;
-                        }
+    /// Returns the mobility advantage in the moving player's perspective.
+    ///
+    /// Only bugs on top of the Hive can move, and each one scores with diminishing returns, so that a single free Ant does
+    /// not outweigh everything else.
+    fn mobility(params: &EvalParams, board: &Board, reach: &Reach) -> f64
+    {
+        let mut score = 0.0;
 
-                        piece_score = piece_score.max(Self::bug_value(neighbour.kind));
-                    }
-                }
-            }
+        for (piece, _, destinations) in reach.bugs()
+        {
+            let piece_score = Self::piece_mobility(board, &piece, destinations).score();
+            score += if piece.player == board.to_move() { piece_score } else { -piece_score };
+        }
 
-            // Heavily reweight a bug that is on a stack, because it is pinning something underneath!
-            if stacking
-            {
-                piece_score *= K_STACKING;
-            }
+        params.k_mobility * score
+    }
+
+    /// Counts the destinations, including Mosquito borrowed moves, and the throws of a bug on top of the Hive.
+    pub(super) fn piece_mobility(board: &Board, piece: &Piece, destinations: &HashSet<Hex>) -> PieceMobility
+    {
+        PieceMobility {
+            destinations: destinations.len(),
+            throws:       board.throw_options(piece),
+        }
+    }
+
+    /// Works out how a bug on top of the Hive counts towards its owner's material.
+    pub(super) fn piece_material(params: &EvalParams, board: &Board, hex: Hex, piece: Piece) -> PieceMaterial
+    {
+        let mut material = PieceMaterial {
+            value:     params.bug_value(piece.kind),
+            copies:    None,
+            // Discard pinned bugs.
+            pinned:    board.is_pinned(&piece),
+            stacked:   board.stacked(&piece),
+            attacking: false,
+        };
 
-            // Bugs attacking their enemy queen should attack in worst-value-first order, to save high-value pieces for overall pressure in the Hive.
-            if let Some(enemy_queen_loc) = board.queen(piece.player.flip())
+        if material.pinned
+        {
+            return material;
+        }
+
+        // Special check to rescore a mosquito as its best possible neighbour.
+        'mosquito_rescore: {
+            if piece.kind == Bug::Mosquito
             {
-                if board.field().neighbours(hex).iter().any(|adj| *adj == enemy_queen_loc)
+                if material.stacked
                 {
-                    piece_score = 0.0;
+                    material.value = params.bug_value(Bug::Beetle);
+                    material.copies = Some(Bug::Beetle);
+                    break 'mosquito_rescore;
                 }
-            }
+                for neighbour in board.pieces_neighbouring(hex)
+                {
+                    if neighbour.kind == Bug::Queen
+                    {
+                        continue;
+                    }
 
-            // Invert it if it does not belong to the current player.
-            if piece.player != board.to_move()
-            {
-                piece_score *= -1.0;
+                    if params.bug_value(neighbour.kind) > material.value
+                    {
+                        material.value = params.bug_value(neighbour.kind);
+                        material.copies = Some(neighbour.kind);
+                    }
+                }
             }
+        }
 
-            score += piece_score;
+        // Bugs attacking their enemy queen should attack in worst-value-first order, to save high-value pieces for overall pressure in the Hive.
+        if let Some(enemy_queen_loc) = board.queen(piece.player.flip())
+        {
+            material.attacking = board.field().neighbours(hex).iter().any(|adj| *adj == enemy_queen_loc);
         }
 
-        K_MOVEABLE * score
+        material
     }
 
     /// Returns a metric calculating the relative safety of the queens. This includes pillbug defense, if possible!
-    fn queens(board: &Board) -> f64
+    fn queens(params: &EvalParams, board: &Board) -> f64
+    {
+        let to_move = board.to_move();
+        let score = Self::queen_terms(params, board, to_move).total() - Self::queen_terms(params, board, to_move.flip()).total();
+        params.k_queens * score
+    }
+
+    /// Scores the safety of one player's queen, term by term, from that player's perspective.
+    pub(super) fn queen_terms(params: &EvalParams, board: &Board, player: Player) -> QueenTerms
     {
-        fn queen_score_for(board: &Board, player: Player) -> f64
+        let mut terms = QueenTerms::default();
+        let crawlers = vec![Bug::Ant, Bug::Mosquito, Bug::Pillbug, Bug::Queen, Bug::Spider];
+
+        // Check for the safety of the friendly queen.
+        let Some(queen_hex) = board.queen(player)
+        else
         {
-            let mut score = 0.0;
-            let crawlers = vec![Bug::Ant, Bug::Mosquito, Bug::Pillbug, Bug::Queen, Bug::Spider];
+            return terms;
+        };
+
+        let queen = Piece {
+            player,
+            kind: Bug::Queen,
+            num: 1,
+        };
 
-            // Check for the safety of the friendly queen.
-            if let Some(queen_hex) = board.queen(player)
+        for neighbour in board.neighbours(queen_hex)
+        {
+            // If this bug is friendly, we can assume the best about its future moves.
+            // For instance, it vacates killspots, or performs good warps.
+            if neighbour.player == player
             {
-                let queen = Piece {
-                    player,
-                    kind: Bug::Queen,
-                    num: 1,
+                // If the bug is a crawler, then it's blocked if we gave it Ant powers
+                // and it still couldn't vacate the hex it's on.
+                let from = board.location(&neighbour).unwrap();
+                let is_blocked = crawlers.contains(&neighbour.kind) && board.is_blocked_crawler(from);
+
+                // Check if the queen's killspots are filled.
+                // If we can vacate a killspot, it is not that severe.
+                terms.killspots -= if is_blocked || board.is_pinned(&neighbour)
+                {
+                    params.k_queen_neighbourhood
+                }
+                else
+                {
+                    params.k_queen_neighbourhood / 2.0
                 };
 
-                for neighbour in board.neighbours(queen_hex)
+                // Check if a friendly pillbug or mosquito can warp the queen to safety.
+                if board.can_throw_another(&neighbour) && !board.is_pinned(&queen)
                 {
-                    // If this bug is friendly, we can assume the best about its future moves.
-                    // For instance, it vacates killspots, or performs good warps.
-                    if neighbour.player == player
-                    {
-                        // If the bug is a crawler, then it's blocked if we gave it Ant powers
-                        // and it still couldn't vacate the hex it's on.
-                        let from = board.location(&neighbour).unwrap();
-                        let is_blocked = crawlers.contains(&neighbour.kind) && board.is_blocked_crawler(from);
-
-                        // Check if the queen's killspots are filled.
-                        // If we can vacate a killspot, it is not that severe.
-                        score -= if is_blocked || board.is_pinned(&neighbour)
-                        {
-                            K_QUEEN_NEIGHBOURHOOD
-                        }
-                        else
-                        {
-                            K_QUEEN_NEIGHBOURHOOD / 2.0
-                        };
-
-                        // Check if a friendly pillbug or mosquito can warp the queen to safety.
-                        if board.can_throw_another(&neighbour) && !board.is_pinned(&queen)
-                        {
-                            let mut escapes: Vec<usize> = Vec::new();
-
-                            let from = queen_hex;
-                            let intermediate = board.location(&neighbour).unwrap();
-                            for to in hex::neighbours(intermediate)
-                            {
-                                let open_killspots = 6 - board.field().neighbours(to).len();
-                                if !board.occupied(to) && board.check_throw_via(from, neighbour, to).is_ok()
-                                {
-                                    escapes.push(open_killspots);
-                                }
-                            }
-
-                            // If we have a suitable defense, reward even further.
-                            let best = escapes.into_iter().max().unwrap_or(0);
-                            if best > MINIMUM_OPEN_KILLSPOTS
-                            {
-                                score += K_DEFENSE;
-                            }
-                        }
-                    }
-                    // Otherwise, we can assume the bugs will not vacate killspots except for in exceptional tempo cases.
-                    else
+                    // If we have a suitable defense, reward even further.
+                    let best = Self::throw_escapes(board, queen_hex, neighbour).max().unwrap_or(0);
+                    if best > MINIMUM_OPEN_KILLSPOTS
                     {
-                        // There is a heavy penalty to having a killspot filled.
-                        score -= K_QUEEN_NEIGHBOURHOOD * ATTACKING_KILLSPOT;
-
-                        // Check how much damage an opponent pillbug could do to the queen's position.
-                        if board.can_throw_another(&neighbour) && !board.is_pinned(&queen)
-                        {
-                            let mut escapes: Vec<usize> = Vec::new();
-
-                            let from = queen_hex;
-                            let intermediate = board.location(&neighbour).unwrap();
-                            for to in hex::neighbours(intermediate)
-                            {
-                                let open_killspots = 6 - board.field().neighbours(to).len();
-                                if !board.occupied(to) && board.check_throw_via(from, neighbour, to).is_ok()
-                                {
-                                    escapes.push(open_killspots);
-                                }
-                            }
-
-                            let best = escapes.into_iter().min().unwrap_or(6);
-                            if best <= MINIMUM_OPEN_KILLSPOTS
-                            {
-                                score -= K_QUEEN_NEIGHBOURHOOD;
-                            }
-                        }
+                        terms.escapes += params.k_defense;
                     }
                 }
+            }
+            // Otherwise, we can assume the bugs will not vacate killspots except for in exceptional tempo cases.
+            else
+            {
+                // There is a heavy penalty to having a killspot filled.
+                terms.killspots -= params.k_queen_neighbourhood * params.attacking_killspot;
 
-                // Finally, if the pillbug itself is not placed, and we could direct-drop it next to the queen, add a contingency reward.
-                let pillbug = Piece {
-                    player,
-                    kind: Bug::Pillbug,
-                    num: 1,
-                };
-
-                if board.location(&pillbug).is_none()
+                // Check how much damage an opponent pillbug could do to the queen's position.
+                if board.can_throw_another(&neighbour) && !board.is_pinned(&queen)
                 {
-                    for neighbour in hex::neighbours(queen_hex)
+                    let best = Self::throw_escapes(board, queen_hex, neighbour).min().unwrap_or(6);
+                    if best <= MINIMUM_OPEN_KILLSPOTS
                     {
-                        // If we found an empty neighbour with no unfriendly neighbours, we succeeded.
-                        if !board.occupied(neighbour) && !board.neighbours(neighbour).iter().any(|piece| piece.player != player)
-                        {
-                            score += K_DEFENSE / 2.0;
-                            break;
-                        }
+                        terms.threats -= params.k_queen_neighbourhood;
                     }
                 }
             }
+        }
 
-            score
+        // Finally, if the pillbug itself is not placed, and we could direct-drop it next to the queen, add a contingency reward.
+        let pillbug = Piece {
+            player,
+            kind: Bug::Pillbug,
+            num: 1,
+        };
+
+        if board.location(&pillbug).is_none()
+        {
+            for neighbour in hex::neighbours(queen_hex)
+            {
+                // If we found an empty neighbour with no unfriendly neighbours, we succeeded.
+                if !board.occupied(neighbour) && !board.neighbours(neighbour).iter().any(|piece| piece.player != player)
+                {
+                    terms.pillbug_drop += params.k_defense / 2.0;
+                    break;
+                }
+            }
         }
 
+        terms
+    }
+
+    /// Counts the open killspots around every hex that a bug next to the queen could throw it to.
+    fn throw_escapes(board: &Board, queen_hex: Hex, thrower: Piece) -> impl Iterator<Item = usize> + '_
+    {
+        let intermediate = board.location(&thrower).unwrap();
+
+        hex::neighbours(intermediate)
+            .into_iter()
+            .filter(move |to| !board.occupied(*to) && board.check_throw_via(queen_hex, thrower, *to).is_ok())
+            .map(|to| 6 - board.field().neighbours(to).len())
+    }
+
+    /// Returns the queen race in the moving player's perspective, which is how many more tempi the opponent needs to
+    /// surround our queen than we need to surround theirs.
+    fn race(params: &EvalParams, board: &Board, reach: &Reach) -> f64
+    {
         let to_move = board.to_move();
-        let score = queen_score_for(board, to_move) - queen_score_for(board, to_move.flip());
-        K_QUEENS * score
+        let score = Self::race_for(board, reach, to_move) - Self::race_for(board, reach, to_move.flip());
+        params.k_race * score
+    }
+
+    /// Estimates how many tempi the opponent needs to surround one player's queen, which is good for that player.
+    ///
+    /// Nobody is racing until both queens are in the Hive.
+    pub(super) fn race_for(board: &Board, reach: &Reach, player: Player) -> f64
+    {
+        let (Some(queen_hex), Some(_)) = (board.queen(player), board.queen(player.flip()))
+        else
+        {
+            return 0.0;
+        };
+
+        Self::surround_tempi(board, reach, queen_hex, player.flip()).into_iter().sum::<usize>() as f64
+    }
+
+    /// Estimates the fewest moves the attacker needs to fill each open killspot around the queen on the given hex.
+    ///
+    /// This is an approximation. A killspot takes one tempo if a free attacking bug that is not already next to the queen
+    /// can move there, Grasshopper jumps included, and two if the attacker has an Ant in hand and the killspot is not
+    /// gated, since an Ant placed anywhere can crawl there next. Every other killspot counts as three: other bugs placed
+    /// from hand, and bugs that need more than one move, are not worked out, as trying each bug in hand on every
+    /// placement would cost more than the rest of the evaluation.
+    pub(super) fn surround_tempi(board: &Board, reach: &Reach, queen_hex: Hex, attacker: Player) -> Vec<usize>
+    {
+        let killspots = hex::neighbours(queen_hex);
+        let open = killspots.into_iter().filter(|hex| !board.occupied(*hex)).collect::<Vec<_>>();
+        if open.is_empty()
+        {
+            return Vec::new();
+        }
+
+        // Bugs already on a killspot would only trade one for another, so they do not count.
+        let reachable = reach
+            .bugs()
+            .filter(|(piece, hex, _)| piece.player == attacker && !killspots.contains(hex))
+            .flat_map(|(_, _, destinations)| destinations.iter().copied())
+            .filter(|hex| open.contains(hex))
+            .collect::<HashSet<Hex>>();
+
+        // An open killspot is next to the queen, so it is on the perimeter unless it is gated.
+        let ant_in_hand = board.pouch().peek(attacker, Bug::Ant).is_some();
+
+        open.into_iter()
+            .map(|hex| {
+                if reachable.contains(&hex)
+                {
+                    1
+                }
+                else if ant_in_hand && !board.field().is_gated(hex)
+                {
+                    2
+                }
+                else
+                {
+                    MAXIMUM_RACE_TEMPI
+                }
+            })
+            .collect()
     }
 
     /// Returns the in-hand advantage in the moving player's perspective.
-    fn reserve(board: &Board) -> f64
+    fn reserve(params: &EvalParams, board: &Board) -> f64
+    {
+        let to_move = board.to_move();
+        let score = Self::reserve_for(params, board, to_move) - Self::reserve_for(params, board, to_move.flip());
+        params.k_reserve * score
+    }
+
+    /// Scores the bugs one player has left in hand.
+    pub(super) fn reserve_for(params: &EvalParams, board: &Board, player: Player) -> f64
+    {
+        let mut score = 0.0;
+        for bug in Bug::all().iter()
+        {
+            let remaining = board.pouch().peek(player, *bug).unwrap_or(0);
+            score += params.bug_value(*bug) + remaining as f64;
+        }
+        score
+    }
+}
+
+/// Where each bug on top of the Hive can move to, worked out once per evaluation so that the mobility and race terms
+/// can share it.
+///
+/// A player's free Ants can nearly always reach the same hexes around the Hive, so they all share the destinations of
+/// their lowest-numbered free Ant. Crawling the whole perimeter for every Ant would halve the search speed.
+pub(super) struct Reach
+{
+    bugs:         Vec<(Piece, Hex, usize)>,
+    destinations: Vec<HashSet<Hex>>,
+}
+
+impl Reach
+{
+    /// Finds the destinations of every bug on top of the Hive.
+    pub(super) fn new(board: &Board) -> Reach
     {
-        fn reserve_for(board: &Board, player: Player) -> f64
+        let mut reach = Reach {
+            bugs:         Vec::new(),
+            destinations: Vec::new(),
+        };
+        let mut ants = [None; 2];
+        let occupied: HashSet<Hex> = board.field().clone().into();
+
+        for (hex, piece) in occupied.into_iter().filter_map(|hex| board.top(hex).map(|piece| (hex, piece)))
         {
-            let mut score = 0.0;
-            for bug in Bug::all().iter()
+            let index = match piece.kind
             {
-                let remaining = board.pouch().peek(player, *bug).unwrap_or(0);
-                score += StrongestEvaluator::bug_value(*bug) + remaining as f64;
-            }
-            score
+                | Bug::Ant if !board.is_pinned(&piece) =>
+                {
+                    *ants[piece.player as usize].get_or_insert_with(|| reach.insert(Self::ant_destinations(board, piece.player)))
+                }
+                | _ => reach.insert(board.destinations(&piece)),
+            };
+            reach.bugs.push((piece, hex, index));
         }
 
-        let to_move = board.to_move();
-        let score = reserve_for(board, to_move) - reserve_for(board, to_move.flip());
-        K_RESERVE * score
+        reach
+    }
+
+    /// Every bug on top of the Hive, with its hex and the hexes it can move to.
+    pub(super) fn bugs(&self) -> impl Iterator<Item = (Piece, Hex, &HashSet<Hex>)> + '_
+    {
+        self.bugs.iter().map(|(piece, hex, index)| (*piece, *hex, &self.destinations[*index]))
+    }
+
+    fn insert(&mut self, destinations: HashSet<Hex>) -> usize
+    {
+        self.destinations.push(destinations);
+        self.destinations.len() - 1
+    }
+
+    /// Finds the destinations of a player's lowest-numbered Ant that can move.
+    fn ant_destinations(board: &Board, player: Player) -> HashSet<Hex>
+    {
+        (1..=3)
+            .map(|num| {
+                let ant = Piece {
+                    player,
+                    kind: Bug::Ant,
+                    num,
+                };
+                board.destinations(&ant)
+            })
+            .find(|destinations| !destinations.is_empty())
+            .unwrap_or_default()
     }
 }
@@ -0,0 +1,150 @@
+use crate::prelude::*;
+
+const HEXES: usize = hex::consts::SIZE as usize;
+const PIECES: usize = piece::consts::COUNT as usize;
+
+/// The deepest ply that keeps its own killer slots.
+const MAX_PLY: usize = 128;
+
+#[derive(Clone, Debug)]
+/// Remembers a reply that refuted each previous move, indexed by the previous move's piece and destination hex.
+pub struct CountermoveTable
+{
+    replies: Box<[[Option<Move>; HEXES]; PIECES]>,
+}
+
+impl Default for CountermoveTable
+{
+    fn default() -> Self
+    {
+        CountermoveTable {
+            replies: Box::new([[None; HEXES]; PIECES]),
+        }
+    }
+}
+
+impl CountermoveTable
+{
+    /// Forgets everything in the table.
+    pub fn clear(&mut self)
+    {
+        self.replies.iter_mut().for_each(|row| row.fill(None));
+    }
+
+    /// Gets the reply that last refuted moving a piece to a particular hex.
+    pub fn get(&self, piece: Piece, to: Hex) -> Option<Move>
+    {
+        self.replies[piece.index() as usize][to as usize]
+    }
+
+    /// Remembers a reply that refuted moving a piece to a particular hex.
+    pub fn store(&mut self, piece: Piece, to: Hex, reply: Move)
+    {
+        self.replies[piece.index() as usize][to as usize] = Some(reply);
+    }
+}
+
+#[derive(Clone, Debug)]
+/// A butterfly table that remembers which quiet moves caused cutoffs, indexed by piece and destination hex.
+pub struct HistoryTable
+{
+    scores: Box<[[i32; HEXES]; PIECES]>,
+}
+
+impl Default for HistoryTable
+{
+    fn default() -> Self
+    {
+        HistoryTable {
+            scores: Box::new([[0; HEXES]; PIECES]),
+        }
+    }
+}
+
+impl HistoryTable
+{
+    /// The largest magnitude a history score can reach; bonuses shrink as they approach it.
+    pub const MAX: i32 = 1 << 14;
+
+    /// Forgets everything in the table.
+    pub fn clear(&mut self)
+    {
+        self.scores.iter_mut().for_each(|row| row.fill(0));
+    }
+
+    /// Rewards a quiet move that caused a cutoff at the given depth.
+    pub fn reward(&mut self, piece: Piece, to: Hex, depth: Depth)
+    {
+        self.update(piece, to, Self::bonus(depth));
+    }
+
+    /// Gets the history score for moving a piece to a particular hex.
+    pub fn score(&self, piece: Piece, to: Hex) -> i32
+    {
+        self.scores[piece.index() as usize][to as usize]
+    }
+}
+
+impl HistoryTable
+{
+    /// The size of a history update at a given depth.
+    fn bonus(depth: Depth) -> i32
+    {
+        depth.squared().clamp(1, Self::MAX)
+    }
+
+    /// Applies a bonus with gravity, so that scores saturate at the table maximum instead of overflowing.
+    fn update(&mut self, piece: Piece, to: Hex, bonus: i32)
+    {
+        let entry = &mut self.scores[piece.index() as usize][to as usize];
+        *entry += bonus - *entry * bonus.abs() / Self::MAX;
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Two killer slots per ply, holding the most recent quiet moves that caused cutoffs at that ply.
+pub struct KillerTable
+{
+    slots: Box<[[Option<Move>; 2]; MAX_PLY]>,
+}
+
+impl Default for KillerTable
+{
+    fn default() -> Self
+    {
+        KillerTable {
+            slots: Box::new([[None; 2]; MAX_PLY]),
+        }
+    }
+}
+
+impl KillerTable
+{
+    /// Forgets every killer.
+    pub fn clear(&mut self)
+    {
+        self.slots.fill([None; 2]);
+    }
+
+    /// Gets the killers at a ply, most recent first.
+    pub fn get(&self, ply: usize) -> [Option<Move>; 2]
+    {
+        self.slots.get(ply).copied().unwrap_or([None; 2])
+    }
+
+    /// Remembers a killer at a ply, pushing out the older of the two.
+    pub fn store(&mut self, ply: usize, mv: Move)
+    {
+        let Some(slots) = self.slots.get_mut(ply)
+        else
+        {
+            return;
+        };
+
+        if slots[0] != Some(mv)
+        {
+            slots[1] = slots[0];
+            slots[0] = Some(mv);
+        }
+    }
+}
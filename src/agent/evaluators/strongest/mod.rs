@@ -1,15 +1,31 @@
-use std::{sync::atomic::Ordering, thread};
+use std::{collections::HashMap, sync::atomic::Ordering, thread};
 
 use itertools::Itertools;
 use rand::{seq::SliceRandom, thread_rng};
 
 use crate::prelude::*;
 
+mod breakdown;
 mod data;
 mod evaluate;
+mod heuristics;
+mod ordering;
+mod params;
 mod search;
+mod skill;
+mod stats;
+mod tuning;
 
+pub use breakdown::*;
 use data::*;
+pub use evaluate::LeafEvaluation;
+use evaluate::Reach;
+use heuristics::*;
+use stats::*;
+pub use ordering::{MovePicker, PrioritizingMoveGenerator, Stage};
+pub use params::EvalParams;
+pub use skill::Skill;
+pub use tuning::Tuner;
 
 #[derive(Debug)]
 /// An evaluator based on alpha-beta search with a set of custom heuristics.
@@ -21,7 +37,7 @@ pub struct StrongestEvaluator
 
 impl Evaluator for StrongestEvaluator
 {
-    type Generator = PrioritizingMoveGenerator;
+    type Generator<'a> = PrioritizingMoveGenerator<'a>;
 
     fn best_move(&mut self, board: &Board, args: SearchArgs) -> Move
     {
@@ -44,35 +60,113 @@ impl Evaluator for StrongestEvaluator
         }
     }
 
-    fn generate_moves(board: &Board) -> Self::Generator
+    fn clear(&mut self)
+    {
+        self.global_data.clear();
+        self.thread_data.iter_mut().for_each(ThreadData::clear);
+    }
+
+    fn depth(&self) -> Depth
+    {
+        self.thread_data.iter().map(|t| t.completed).max().unwrap_or(Depth::NIL)
+    }
+
+    fn generate_moves(board: &Board) -> Self::Generator<'_>
     {
         PrioritizingMoveGenerator::new(board, false)
     }
 
+    fn load_table(&mut self, path: &std::path::Path) -> Result<usize>
+    {
+        self.global_data.transpositions.restore(path)
+    }
+
+    fn memory(&self) -> usize
+    {
+        self.global_data.transpositions.memory() + self.global_data.cache.memory()
+    }
+
     fn new(options: UhpOptions) -> Self
     {
-        let global_data = GlobalData::new(&options);
+        Self::with_leaf_evaluation(options, LeafEvaluation::Heuristic)
+    }
+
+    fn new_game(&mut self)
+    {
+        // Cached positions are only a function of the position, so they stay useful between games.
+        self.global_data.transpositions.clear();
+        self.thread_data.iter_mut().for_each(ThreadData::clear);
+    }
+
+    fn nodes(&self) -> u64
+    {
+        self.thread_data.iter().map(|t| t.stats.nodes.total()).sum()
+    }
+
+    fn save_table(&self, path: &std::path::Path) -> Result<usize>
+    {
+        self.global_data.transpositions.save(path)
+    }
+
+    fn set_options(&mut self, options: &UhpOptions)
+    {
+        self.global_data.resize(options);
+    }
+}
+
+impl StrongestEvaluator
+{
+    /// Creates an evaluator that scores the positions it searches with the given static evaluation.
+    pub fn with_leaf_evaluation(options: UhpOptions, leaf_evaluation: LeafEvaluation) -> Self
+    {
+        let mut global_data = GlobalData::new(&options);
+        global_data.leaf_evaluation = leaf_evaluation;
 
         StrongestEvaluator {
             global_data,
             thread_data: Vec::new(),
         }
     }
-}
 
-impl StrongestEvaluator
-{
-    /// Gets the best thread by the score of its variation.
+    /// Gets the thread whose move wins a vote between the threads, where each vote is weighted by how deep the thread
+    /// searched and how much better its score is than the worst one. A proven win found by any thread wins outright.
     fn best_thread(&self) -> &ThreadData
     {
-        self.thread_data.iter().max_by_key(|t| t.variation.score).unwrap()
+        // Keeps threads whose scores are all close from voting with nearly zero weight.
+        const VOTE_OFFSET: i32 = 14;
+
+        let voters = self.thread_data.iter().filter(|t| t.best_move.is_some());
+        let Some(lowest) = voters.clone().map(|t| t.target).min()
+        else
+        {
+            return &self.thread_data[0];
+        };
+
+        if let Some(winner) = voters.clone().filter(|t| scores::reconstruct(t.target) == MINIMUM_WIN).max_by_key(|t| t.target)
+        {
+            return winner;
+        }
+
+        let mut votes: HashMap<Move, i64> = HashMap::new();
+        for t in voters.clone()
+        {
+            let weight = (t.target - lowest + VOTE_OFFSET) as i64 * t.completed.floor() as i64;
+            *votes.entry(t.best_move.unwrap()).or_default() += weight;
+        }
+
+        voters.max_by_key(|t| (votes[&t.best_move.unwrap()], t.completed, t.target)).unwrap()
     }
 
-    /// Creates the thread data on this evaluator.
-    fn create_thread_data<'a>(&mut self, board: &Board)
+    /// Creates the thread data on this evaluator, unless there is already data for the right number of threads.
+    fn create_thread_data(&mut self, board: &Board)
     {
-        let num_threads = std::thread::available_parallelism().map(|nzu| nzu.into()).unwrap_or(1);
-        let mut template = ThreadData::new(self.global_data.options.clone(), board);
+        let num_threads = self.global_data.options.num_threads.max(1);
+        if self.thread_data.len() == num_threads
+        {
+            return;
+        }
+
+        let mut template = ThreadData::new(board);
         self.thread_data.clear();
 
         for thread_id in 0..num_threads
@@ -121,7 +215,7 @@ impl StrongestEvaluator
     fn search(&mut self, board: &Board, args: SearchArgs) -> Move
     {
         self.create_thread_data(board);
-        self.setup_data(args);
+        self.setup_data(board, args);
 
         thread::scope(|s| {
             let global_data = &self.global_data;
@@ -144,23 +238,37 @@ impl StrongestEvaluator
             }
         });
 
+        let root = board;
         let mut board = board.clone();
         let mut movegen = super::PrioritizingMoveGenerator::new(&board, false);
 
         let best_thread = self.best_thread();
         let variation = best_thread.variation.clone();
 
-        let entry = self.global_data.transpositions.load(board.zobrist()).unwrap();
-        let mv = Option::<Move>::from(entry.mv).unwrap_or(variation.moves.get(0).map(|sm| sm.mv).unwrap_or(movegen.next().unwrap_or(Move::Pass)));
+        let mv = best_thread
+            .best_move
+            .or(variation.moves.first().map(|sm| sm.mv))
+            .unwrap_or_else(|| movegen.next().unwrap_or(Move::Pass));
+
+        // A handicapped search may settle for one of the other candidates.
+        let skill = self.global_data.options.skill();
+        let mv = match skill.is_limited()
+        {
+            | true => skill.pick(&best_thread.root_moves, &mut thread_rng()).map_or(mv, |sm| sm.mv),
+            | false => mv,
+        };
 
         let p = board.to_move();
         board.play(&mv).expect("illegal move");
 
-        let e = -Self::evaluate_board(&board);
-        let s = variation.score;
+        let e = -Self::evaluate(&self.global_data, &board);
+        let s = best_thread.target;
+
+        let mut stats = SearchStats::default();
+        self.thread_data.iter().for_each(|t| stats += &t.stats);
 
-        let lct = self.thread_data.iter().map(|t| t.leaf_count).sum::<u64>();
-        let sct = self.thread_data.iter().map(|t| t.stem_count).sum::<u64>();
+        let lct = stats.nodes.leaves();
+        let sct = stats.nodes.stems;
         let elapsed = self.global_data.start_time.elapsed();
         let el = elapsed.as_secs_f64().round();
         let d = variation.moves.len();
@@ -168,6 +276,16 @@ impl StrongestEvaluator
         let sr = (sct as f64 / el).round() as i32;
         let el = el as i32;
 
+        let cuts = stats.cutoffs.total;
+        let fc = stats.cutoffs.first_move_rate();
+        let (ar, aw) = (stats.aspiration.researches(), stats.aspiration.windows);
+
+        let cache = &self.global_data.cache;
+        let (eh, ec) = (cache.evals.hit_rate(), cache.evals.lookups());
+        let (mh, mc) = (cache.movements.hit_rate(), cache.movements.lookups());
+        let hf = self.global_data.transpositions.hashfull();
+        let mb = self.memory() as f64 / 1e+6;
+
         let ms = format!("{}", mv);
         let is_variation = best_thread.best_move.is_none();
         let pv = variation.moves.iter().map(|mv| format!("{}", mv.mv)).join(";");
@@ -186,54 +304,49 @@ impl StrongestEvaluator
 player to move: {p}
 principal variation: {pv}
 variation move? {is_variation}
+cutoffs on first move: {fc:.1}% of {cuts}
+aspiration re-searches: {ar} in {aw} windows
+cache hits: evals {eh:.1}% of {ec}, movements {mh:.1}% of {mc}
+hashfull: {hf}‰, memory: {mb:.1} MB
 
 "
         );
 
-        mv
-    }
-
-    /// Sets up the thread data and global data to prepare for a search.
-    fn setup_data(&mut self, args: SearchArgs)
-    {
-        self.global_data.prepare(args);
-        for t in self.thread_data.iter_mut()
+        if self.global_data.telemetry.is_enabled()
         {
-            t.prepare();
+            let record = SearchRecord {
+                position: GameString::from(root).to_string(),
+                mv: ms,
+                score: s,
+                pv: variation.moves.iter().map(|sm| sm.mv.to_string()).collect(),
+                depth: self.depth().floor(),
+                threads: self.thread_data.len(),
+                time_ms: elapsed.as_secs_f64() * 1e+3,
+                hashfull: hf,
+                branching_factor: branching_factor(&best_thread.iterations),
+                stats,
+                iterations: best_thread.iterations.clone(),
+            };
+
+            if let Err(err) = self.global_data.telemetry.record(&record)
+            {
+                log::warn!("{}", err);
+            }
         }
-    }
-}
-
-/// A move generator that tries to generate better moves first for the purposes of
-/// making position evaluation more efficient.
-pub struct PrioritizingMoveGenerator
-{
-    moves: Vec<Move>,
-    index: usize,
-}
 
-impl Iterator for PrioritizingMoveGenerator
-{
-    type Item = Move;
-    fn next(&mut self) -> Option<Self::Item>
-    {
-        let next = self.moves.get(self.index);
-        self.index += 1;
-        next.copied()
+        mv
     }
-}
 
-impl<'a> PrioritizingMoveGenerator
-{
-    pub fn new(board: &Board, standard_position: bool) -> Self
+    /// Sets up the thread data and global data to prepare for a search.
+    fn setup_data(&mut self, board: &Board, args: SearchArgs)
     {
-        let mut moves = board.generate_moves(standard_position);
+        self.global_data.prepare(args);
 
-        if moves.is_empty()
+        let nodes = args.nodes().min(self.global_data.options.skill().nodes());
+        let node_budget = nodes / self.thread_data.len().max(1) as u64;
+        for t in self.thread_data.iter_mut()
         {
-            moves.push(Move::Pass);
+            t.prepare(board, node_budget.max(1));
         }
-
-        PrioritizingMoveGenerator { moves, index: 0 }
     }
 }
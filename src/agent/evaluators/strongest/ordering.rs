@@ -0,0 +1,372 @@
+use std::sync::Arc;
+
+use arrayvec::ArrayVec;
+
+use super::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// The stages of move generation, in the order that they are tried.
+pub enum Stage
+{
+    /// The move stored in the transposition table, if it is legal here.
+    TableMove,
+
+    /// Movements that fill a liberty of the enemy queen, or climb on top of it.
+    Attacks,
+
+    /// Quiet moves that caused cutoffs in sibling positions, followed by the countermove to the previous move.
+    Killers,
+
+    /// Pillbug and Mosquito-as-Pillbug throws.
+    Throws,
+
+    /// Everything else, including placements, in history order.
+    Quiets,
+
+    /// There were no moves at all, so the only option is to pass.
+    Pass,
+
+    /// The generator is exhausted.
+    Done,
+}
+
+#[derive(Clone, Debug)]
+/// A staged move picker, which only generates a stage once every earlier stage has been tried.
+///
+/// The picker does not hold onto the board, so that the caller is free to play and undo moves between calls.
+pub struct MovePicker
+{
+    current:           Stage,
+    standard_position: bool,
+    table_move:        Option<Move>,
+    killers:           [Option<Move>; 3],
+    tried:             ArrayVec<Move, 5>,
+    movements:         Option<Arc<[Move]>>,
+    generated:         bool,
+    attacks:           Vec<Move>,
+    pending:           Vec<ScoredMove>,
+    index:             usize,
+    yielded:           usize,
+    yielded_from:      Stage,
+}
+
+impl MovePicker
+{
+    /// Creates a picker for the player to move.
+    pub fn new(standard_position: bool) -> Self
+    {
+        MovePicker {
+            current: Stage::TableMove,
+            standard_position,
+            table_move: None,
+            killers: [None; 3],
+            tried: ArrayVec::new(),
+            movements: None,
+            generated: false,
+            attacks: Vec::new(),
+            pending: Vec::new(),
+            index: 0,
+            yielded: 0,
+            yielded_from: Stage::TableMove,
+        }
+    }
+
+    /// The movements this picker had to generate itself, if it got that far, so that they can be cached.
+    pub fn generated_movements(&self) -> Option<Arc<[Move]>>
+    {
+        self.movements.clone().filter(|_| self.generated)
+    }
+
+    /// Whether a movement by the player to move fills a liberty of the enemy queen, or climbs on top of it.
+    pub fn is_attack(board: &Board, mv: &Move) -> bool
+    {
+        board.queen(board.to_move().flip()).is_some_and(|queen| Self::attacks(board, mv, queen))
+    }
+
+    /// Whether the player to move has exactly one legal move.
+    ///
+    /// This generates the movements if the picker does not have them yet, so that they can be cached. Placements and
+    /// throws are only generated when there is at most one movement.
+    pub fn is_single_reply(&mut self, board: &Board) -> bool
+    {
+        if self.movements.is_none()
+        {
+            self.movements = Some(board.generate_movements().into());
+            self.generated = true;
+        }
+
+        if self.movements().len() > 1
+        {
+            return false;
+        }
+
+        let mut replies = self.movements().to_vec();
+        for mv in board.generate_throws()
+        {
+            if !replies.contains(&mv)
+            {
+                replies.push(mv);
+            }
+        }
+
+        replies.len() + board.generate_placements(self.standard_position).len() == 1
+    }
+
+    /// The stage that the most recently returned move came from.
+    pub fn stage(&self) -> Stage
+    {
+        self.yielded_from
+    }
+
+    /// Sets the countermove to try after the killers.
+    pub fn with_countermove(mut self, countermove: Option<Move>) -> Self
+    {
+        self.killers[2] = countermove;
+        self
+    }
+
+    /// Sets the killer moves to try after the attacks.
+    pub fn with_killers(mut self, killers: [Option<Move>; 2]) -> Self
+    {
+        self.killers[..2].copy_from_slice(&killers);
+        self
+    }
+
+    /// Sets the movements in this position, if they are already known, so that the picker does not generate them.
+    pub fn with_movements(mut self, movements: Option<Arc<[Move]>>) -> Self
+    {
+        self.movements = movements;
+        self
+    }
+
+    /// Sets the move from the transposition table to try first.
+    pub fn with_table_move(mut self, table_move: Option<Move>) -> Self
+    {
+        self.table_move = table_move;
+        self
+    }
+
+    /// Gets the next move for the given board, generating the next stage if necessary.
+    ///
+    /// The board must be in the same position on every call.
+    pub fn next(&mut self, board: &Board, history: Option<&HistoryTable>) -> Option<Move>
+    {
+        loop
+        {
+            let next = match self.current
+            {
+                | Stage::TableMove => self.next_table_move(board),
+                | Stage::Attacks => self.next_pending(),
+                | Stage::Killers => self.next_killer(board),
+                | Stage::Throws | Stage::Quiets => self.next_pending(),
+                | Stage::Pass => (self.yielded == 0).then_some(Move::Pass),
+                | Stage::Done => return None,
+            };
+
+            match next
+            {
+                | Some(mv) =>
+                {
+                    self.yielded += 1;
+                    self.yielded_from = self.current;
+                    return Some(mv);
+                }
+                | None => self.advance(board, history),
+            }
+        }
+    }
+}
+
+impl MovePicker
+{
+    /// Moves on to the next stage, generating its moves.
+    fn advance(&mut self, board: &Board, history: Option<&HistoryTable>)
+    {
+        self.pending.clear();
+        self.index = 0;
+
+        self.current = match self.current
+        {
+            | Stage::TableMove =>
+            {
+                if self.movements.is_none()
+                {
+                    self.movements = Some(board.generate_movements().into());
+                    self.generated = true;
+                }
+
+                let enemy_queen = board.queen(board.to_move().flip());
+                self.attacks = self
+                    .movements()
+                    .iter()
+                    .filter(|mv| enemy_queen.map(|queen| Self::attacks(board, mv, queen)).unwrap_or(false))
+                    .copied()
+                    .collect();
+
+                self.pending = self.fresh(self.attacks.clone()).map(|mv| ScoredMove { mv, score: 0 }).collect();
+                Stage::Attacks
+            }
+            | Stage::Attacks => Stage::Killers,
+            | Stage::Killers =>
+            {
+                let mut throws: Vec<Move> = Vec::new();
+                for mv in board.generate_throws()
+                {
+                    if !self.movements().contains(&mv) && !throws.contains(&mv)
+                    {
+                        throws.push(mv);
+                    }
+                }
+
+                self.pending = self.fresh(throws).map(|mv| ScoredMove { mv, score: 0 }).collect();
+                Stage::Throws
+            }
+            | Stage::Throws =>
+            {
+                let mut quiets: Vec<Move> = self.movements().iter().filter(|mv| !self.attacks.contains(mv)).copied().collect();
+                quiets.extend(board.generate_placements(self.standard_position));
+
+                self.pending = self
+                    .fresh(quiets)
+                    .map(|mv| {
+                        let score = match (history, mv, board.destination(&mv))
+                        {
+                            | (Some(history), Move::Move(piece, _) | Move::Place(piece, _), Some(to)) => history.score(piece, to),
+                            | _ => 0,
+                        };
+                        ScoredMove { mv, score }
+                    })
+                    .collect();
+
+                if history.is_some()
+                {
+                    self.pending.sort_by_key(|sm| -sm.score);
+                }
+                Stage::Quiets
+            }
+            | Stage::Quiets => Stage::Pass,
+            | Stage::Pass | Stage::Done => Stage::Done,
+        };
+    }
+
+    /// Whether a movement fills a liberty of the enemy queen, or climbs on top of it.
+    fn attacks(board: &Board, mv: &Move, queen: Hex) -> bool
+    {
+        let Move::Move(piece, _) = mv
+        else
+        {
+            return false;
+        };
+
+        let Some(to) = board.destination(mv)
+        else
+        {
+            return false;
+        };
+
+        let adjacent = |hex: Hex| hex::neighbours(queen).contains(&hex);
+        let from = board.location(piece).unwrap();
+
+        to == queen || adjacent(to) && !adjacent(from)
+    }
+
+    /// Drops the moves that an earlier stage already tried.
+    fn fresh(&self, moves: Vec<Move>) -> impl Iterator<Item = Move> + '_
+    {
+        moves.into_iter().filter(|mv| !self.tried.contains(mv))
+    }
+
+    /// Tries the killers and the countermove, provided they are legal here and have not been tried yet.
+    fn next_killer(&mut self, board: &Board) -> Option<Move>
+    {
+        while self.index < self.killers.len()
+        {
+            let killer = self.killers[self.index];
+            self.index += 1;
+
+            let Some(mv) = killer
+            else
+            {
+                continue;
+            };
+
+            if self.tried.contains(&mv) || self.attacks.contains(&mv)
+            {
+                continue;
+            }
+
+            let legal = match mv
+            {
+                | Move::Move(..) => self.movements().contains(&mv),
+                | Move::Place(..) => board.is_legal(&mv),
+                | Move::Pass => false,
+            };
+
+            if legal
+            {
+                self.tried.push(mv);
+                return Some(mv);
+            }
+        }
+        None
+    }
+
+    /// The movements in this position, which are known once the attacks have been generated.
+    fn movements(&self) -> &[Move]
+    {
+        self.movements.as_deref().unwrap_or_default()
+    }
+
+    /// Takes the next move from the current stage's list.
+    fn next_pending(&mut self) -> Option<Move>
+    {
+        let next = self.pending.get(self.index).map(|sm| sm.mv);
+        self.index += 1;
+        next
+    }
+
+    /// Tries the table move, provided it is legal here.
+    fn next_table_move(&mut self, board: &Board) -> Option<Move>
+    {
+        let mv = self.table_move.take()?;
+        if board.is_legal(&mv)
+        {
+            self.tried.push(mv);
+            Some(mv)
+        }
+        else
+        {
+            None
+        }
+    }
+}
+
+/// A move generator that tries to generate better moves first for the purposes of
+/// making position evaluation more efficient.
+///
+/// This is a [MovePicker] that borrows its board, so that it can be used as an iterator.
+pub struct PrioritizingMoveGenerator<'a>
+{
+    board:  &'a Board,
+    picker: MovePicker,
+}
+
+impl Iterator for PrioritizingMoveGenerator<'_>
+{
+    type Item = Move;
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        self.picker.next(self.board, None)
+    }
+}
+
+impl<'a> PrioritizingMoveGenerator<'a>
+{
+    pub fn new(board: &'a Board, standard_position: bool) -> Self
+    {
+        PrioritizingMoveGenerator {
+            board,
+            picker: MovePicker::new(standard_position),
+        }
+    }
+}
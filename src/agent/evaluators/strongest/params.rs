@@ -0,0 +1,321 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+/// The weights used by the static evaluation, so that they can be changed without a rebuild.
+///
+/// Every term has an opening weight and an `endgame_` weight, which [`EvalParams::tapered`] blends by the game phase.
+/// Files only need to name the weights they change; the rest keep their defaults.
+pub struct EvalParams
+{
+    /// How much worse a killspot filled by an enemy bug is than one filled by a friendly bug.
+    pub attacking_killspot:            f64,
+    pub k_defense:                     f64,
+    /// The weight of the square root of each free bug's destinations and throws.
+    pub k_mobility:                    f64,
+    pub k_moveable:                    f64,
+    pub k_queen_neighbourhood:         f64,
+    pub k_queens:                      f64,
+    /// The weight of each tempo the opponent needs to surround a player's queen.
+    pub k_race:                        f64,
+    pub k_reserve:                     f64,
+    pub k_stacking:                    f64,
+    pub value_ant:                     f64,
+    pub value_beetle:                  f64,
+    pub value_grasshopper:             f64,
+    pub value_ladybug:                 f64,
+    pub value_mosquito:                f64,
+    pub value_pillbug:                 f64,
+    pub value_queen:                   f64,
+    pub value_spider:                  f64,
+    pub endgame_attacking_killspot:    f64,
+    pub endgame_k_defense:             f64,
+    pub endgame_k_mobility:            f64,
+    pub endgame_k_moveable:            f64,
+    pub endgame_k_queen_neighbourhood: f64,
+    pub endgame_k_queens:              f64,
+    pub endgame_k_race:                f64,
+    pub endgame_k_reserve:             f64,
+    pub endgame_k_stacking:            f64,
+    pub endgame_value_ant:             f64,
+    pub endgame_value_beetle:          f64,
+    pub endgame_value_grasshopper:     f64,
+    pub endgame_value_ladybug:         f64,
+    pub endgame_value_mosquito:        f64,
+    pub endgame_value_pillbug:         f64,
+    pub endgame_value_queen:           f64,
+    pub endgame_value_spider:          f64,
+}
+
+impl Default for EvalParams
+{
+    fn default() -> Self
+    {
+        EvalParams {
+            attacking_killspot:            1.2,
+            k_defense:                     40.0,
+            k_mobility:                    2.0,
+            k_moveable:                    2.0,
+            k_queen_neighbourhood:         30.0,
+            k_queens:                      1.0,
+            k_race:                        4.0,
+            k_reserve:                     1.0,
+            k_stacking:                    2.0,
+            value_ant:                     7.0,
+            value_beetle:                  6.0,
+            value_grasshopper:             3.0,
+            value_ladybug:                 6.0,
+            value_mosquito:                8.0,
+            value_pillbug:                 6.0,
+            value_queen:                   12.0,
+            value_spider:                  2.0,
+            endgame_attacking_killspot:    1.2,
+            endgame_k_defense:             40.0,
+            endgame_k_mobility:            1.5,
+            endgame_k_moveable:            2.0,
+            endgame_k_queen_neighbourhood: 30.0,
+            endgame_k_queens:              1.5,
+            endgame_k_race:                8.0,
+            endgame_k_reserve:             0.5,
+            endgame_k_stacking:            2.0,
+            endgame_value_ant:             7.0,
+            endgame_value_beetle:          6.0,
+            endgame_value_grasshopper:     3.0,
+            endgame_value_ladybug:         6.0,
+            endgame_value_mosquito:        8.0,
+            endgame_value_pillbug:         6.0,
+            endgame_value_queen:           12.0,
+            endgame_value_spider:          2.0,
+        }
+    }
+}
+
+impl EvalParams
+{
+    /// The number of terms, each of which has an opening and an endgame weight.
+    pub const TERMS: usize = 17;
+
+    /// The names of the weights as UHP options, in the same order as [`EvalParams::values`].
+    pub const NAMES: [&'static str; 2 * EvalParams::TERMS] = [
+        "AttackingKillspot",
+        "KDefense",
+        "KMobility",
+        "KMoveable",
+        "KQueenNeighbourhood",
+        "KQueens",
+        "KRace",
+        "KReserve",
+        "KStacking",
+        "ValueAnt",
+        "ValueBeetle",
+        "ValueGrasshopper",
+        "ValueLadybug",
+        "ValueMosquito",
+        "ValuePillbug",
+        "ValueQueen",
+        "ValueSpider",
+        "EndgameAttackingKillspot",
+        "EndgameKDefense",
+        "EndgameKMobility",
+        "EndgameKMoveable",
+        "EndgameKQueenNeighbourhood",
+        "EndgameKQueens",
+        "EndgameKRace",
+        "EndgameKReserve",
+        "EndgameKStacking",
+        "EndgameValueAnt",
+        "EndgameValueBeetle",
+        "EndgameValueGrasshopper",
+        "EndgameValueLadybug",
+        "EndgameValueMosquito",
+        "EndgameValuePillbug",
+        "EndgameValueQueen",
+        "EndgameValueSpider",
+    ];
+
+    /// Gives a baseline value for a piece. The queen value is HIGH, because it refers to moveable queens.
+    ///
+    /// Moveable queens are strong because they can totally neutralize an opponent's tempo by escaping an attack.
+    pub fn bug_value(&self, bug: Bug) -> f64
+    {
+        match bug
+        {
+            | Bug::Ant => self.value_ant,
+            | Bug::Beetle => self.value_beetle,
+            | Bug::Grasshopper => self.value_grasshopper,
+            | Bug::Ladybug => self.value_ladybug,
+            | Bug::Mosquito => self.value_mosquito,
+            | Bug::Pillbug => self.value_pillbug,
+            | Bug::Queen => self.value_queen,
+            | Bug::Spider => self.value_spider,
+        }
+    }
+
+    /// Blends the opening and endgame weights for a game phase between 0 (the opening) and 1 (the endgame).
+    ///
+    /// Only the opening weights of the blend are meaningful, and they are what the static evaluation reads.
+    pub fn tapered(&self, phase: f64) -> EvalParams
+    {
+        let phase = phase.clamp(0.0, 1.0);
+        let values = self.values();
+        let (opening, endgame) = values.split_at(Self::TERMS);
+
+        let mut blended = self.clone();
+        for (i, weight) in blended.values_mut().into_iter().take(Self::TERMS).enumerate()
+        {
+            *weight = opening[i] * (1.0 - phase) + endgame[i] * phase;
+        }
+        blended
+    }
+
+    /// Gets a weight by its option name.
+    pub fn get(&self, name: &str) -> Option<f64>
+    {
+        Self::position(name).map(|i| self.values()[i])
+    }
+
+    /// Gets a mutable reference to a weight by its option name.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut f64>
+    {
+        let i = Self::position(name)?;
+        self.values_mut().into_iter().nth(i)
+    }
+
+    /// Reads weights from a file, as TOML if it has a `.toml` extension and as JSON otherwise.
+    pub fn load(path: &Path) -> Result<EvalParams>
+    {
+        let contents = std::fs::read_to_string(path).map_err(|err| Self::file_error(err.into(), path))?;
+
+        let parsed = if path.extension().is_some_and(|ext| ext == "toml")
+        {
+            toml::from_str::<EvalParams>(&contents).map_err(|err| err.to_string())
+        }
+        else
+        {
+            serde_json::from_str::<EvalParams>(&contents).map_err(|err| err.to_string())
+        };
+
+        parsed.map_err(|msg| Self::file_error(Error::new(Kind::ParseError, msg), path))
+    }
+
+    /// Writes the weights to a file, as TOML if it has a `.toml` extension and as JSON otherwise.
+    pub fn save(&self, path: &Path) -> Result<()>
+    {
+        let contents = if path.extension().is_some_and(|ext| ext == "toml")
+        {
+            toml::to_string(self).map_err(|err| err.to_string())
+        }
+        else
+        {
+            serde_json::to_string_pretty(self).map_err(|err| err.to_string())
+        };
+
+        let contents = contents.map_err(|msg| Error::new(Kind::InternalError, msg))?;
+        std::fs::write(path, contents).map_err(|err| {
+            let base = Error::new(Kind::InvalidOption, format!("Could not save evaluation weights to '{}'.", path.display()));
+            Error::from(err).chain(base)
+        })
+    }
+
+    /// Every weight, in the same order as [`EvalParams::NAMES`].
+    pub fn values(&self) -> [f64; 2 * EvalParams::TERMS]
+    {
+        [
+            self.attacking_killspot,
+            self.k_defense,
+            self.k_mobility,
+            self.k_moveable,
+            self.k_queen_neighbourhood,
+            self.k_queens,
+            self.k_race,
+            self.k_reserve,
+            self.k_stacking,
+            self.value_ant,
+            self.value_beetle,
+            self.value_grasshopper,
+            self.value_ladybug,
+            self.value_mosquito,
+            self.value_pillbug,
+            self.value_queen,
+            self.value_spider,
+            self.endgame_attacking_killspot,
+            self.endgame_k_defense,
+            self.endgame_k_mobility,
+            self.endgame_k_moveable,
+            self.endgame_k_queen_neighbourhood,
+            self.endgame_k_queens,
+            self.endgame_k_race,
+            self.endgame_k_reserve,
+            self.endgame_k_stacking,
+            self.endgame_value_ant,
+            self.endgame_value_beetle,
+            self.endgame_value_grasshopper,
+            self.endgame_value_ladybug,
+            self.endgame_value_mosquito,
+            self.endgame_value_pillbug,
+            self.endgame_value_queen,
+            self.endgame_value_spider,
+        ]
+    }
+
+    /// Mutable references to every weight, in the same order as [`EvalParams::NAMES`].
+    pub fn values_mut(&mut self) -> [&mut f64; 2 * EvalParams::TERMS]
+    {
+        [
+            &mut self.attacking_killspot,
+            &mut self.k_defense,
+            &mut self.k_mobility,
+            &mut self.k_moveable,
+            &mut self.k_queen_neighbourhood,
+            &mut self.k_queens,
+            &mut self.k_race,
+            &mut self.k_reserve,
+            &mut self.k_stacking,
+            &mut self.value_ant,
+            &mut self.value_beetle,
+            &mut self.value_grasshopper,
+            &mut self.value_ladybug,
+            &mut self.value_mosquito,
+            &mut self.value_pillbug,
+            &mut self.value_queen,
+            &mut self.value_spider,
+            &mut self.endgame_attacking_killspot,
+            &mut self.endgame_k_defense,
+            &mut self.endgame_k_mobility,
+            &mut self.endgame_k_moveable,
+            &mut self.endgame_k_queen_neighbourhood,
+            &mut self.endgame_k_queens,
+            &mut self.endgame_k_race,
+            &mut self.endgame_k_reserve,
+            &mut self.endgame_k_stacking,
+            &mut self.endgame_value_ant,
+            &mut self.endgame_value_beetle,
+            &mut self.endgame_value_grasshopper,
+            &mut self.endgame_value_ladybug,
+            &mut self.endgame_value_mosquito,
+            &mut self.endgame_value_pillbug,
+            &mut self.endgame_value_queen,
+            &mut self.endgame_value_spider,
+        ]
+    }
+}
+
+impl EvalParams
+{
+    /// Wraps an error from reading a weights file, naming the file.
+    fn file_error(err: Error, path: &Path) -> Error
+    {
+        let base = Error::new(Kind::InvalidOption, format!("Could not load evaluation weights from '{}'.", path.display()));
+        err.chain(base)
+    }
+
+    /// Finds the index of a weight by its option name.
+    fn position(name: &str) -> Option<usize>
+    {
+        Self::NAMES.iter().position(|n| *n == name)
+    }
+}
@@ -2,6 +2,48 @@ use super::*;
 
 impl StrongestEvaluator
 {
+    /// How far the quiescence search looks past the horizon of the main search.
+    const QUIESCENCE_DEPTH: Depth = Depth::new(2);
+
+    /// How close to the horizon a node must be for futility pruning and razoring.
+    const FUTILITY_DEPTH: Depth = Depth::new(2);
+
+    /// How far outside the window the static evaluation must be, per ply left, for futility pruning.
+    const FUTILITY_MARGIN: i32 = 60;
+
+    /// How far below alpha the static evaluation must be, per ply left, to razor a node.
+    const RAZOR_MARGIN: i32 = 120;
+
+    /// How deep a node must be for its late moves to be reduced.
+    const REDUCTION_DEPTH: Depth = Depth::new(3);
+
+    /// How many moves are searched at full depth before the rest are reduced.
+    const REDUCTION_MOVES: usize = 3;
+
+    /// How much a move that fills a liberty of the enemy queen is extended.
+    const QUEEN_THREAT_EXTENSION: Depth = Depth::raw(50);
+
+    /// How many of its liberties a queen must have lost for its owner to be in too much danger to prune.
+    const DANGER_KILLSPOTS: usize = 4;
+
+    /// How deep an iteration must be to search inside an aspiration window, as shallower scores swing too much.
+    const ASPIRATION_DEPTH: Depth = Depth::new(2);
+
+    /// The radius of the first aspiration window around the score of the last iteration, which doubles on each miss.
+    const ASPIRATION_WINDOW: i32 = 50;
+
+    /// How deep a node must be to try the null move.
+    const NULL_DEPTH: Depth = Depth::new(2);
+
+    /// How much shallower than the node, beyond the pass itself, the null move is searched at the least.
+    const NULL_REDUCTION: Depth = Depth::new(2);
+
+    /// How few free pieces a player without pieces in hand can have before passing might be their best move.
+    const NULL_FREE_PIECES: usize = 3;
+
+    /// How much a single tactical move could gain at most, short of winning, for delta pruning in the quiescence search.
+    const DELTA_MARGIN: i32 = 150;
+
     // Performs alpha-beta search.
     fn alpha_beta(global_data: &GlobalData, thread_data: &mut ThreadData, search_data: ABData, prev: Option<Move>) -> Option<i32>
     {
@@ -11,24 +53,29 @@ impl StrongestEvaluator
             return None;
         }
 
+        if thread_data.stats.nodes.total() >= thread_data.node_budget
+        {
+            global_data.signal();
+            return None;
+        }
+
         let mut data = search_data;
-        thread_data.leaf_count += 1;
+        thread_data.stats.nodes.main += 1;
 
         // If we are in a terminal state, we should also return immediately.
         if matches!(thread_data.board.state(), GameState::WhiteWins | GameState::BlackWins | GameState::Draw)
         {
-            return Some(Self::evaluate_board(&thread_data.board));
+            return Some(Self::static_eval(global_data, &thread_data.board));
         }
 
         // If we have a depth constraint, find extensions using quiescence search, and return the static evaluation at the q-root.
-        if data.depth <= Depth::NIL
+        // Less than a whole ply left, as after an extension at the horizon, is not enough for another main-search move.
+        if data.depth < Depth::PLY
         {
-            const QUIESCENCE_DEPTH: Depth = Depth::new(2);
-
             let q_data = ABData {
                 a:     data.a,
                 b:     data.b,
-                depth: QUIESCENCE_DEPTH,
+                depth: Self::QUIESCENCE_DEPTH,
             };
 
             return Self::quiescence(global_data, thread_data, q_data);
@@ -36,75 +83,161 @@ impl StrongestEvaluator
 
         let pre_alpha = data.a;
         let mut candidate = None;
-        let board = thread_data.board.clone();
 
         // We might have a good move in the table.
-        if let Some(score) = global_data
+        let cutoff = global_data
             .transpositions
-            .check(board.zobrist(), data.depth, &mut candidate, &mut data.a, &mut data.b)
-        {
-            return Some(score);
-        }
+            .check(thread_data.board.zobrist(), data.depth, &mut candidate, &mut data.a, &mut data.b);
 
-        // Null move observation holds?
-        if Self::bugzwang(global_data, thread_data, data.clone())? >= data.b
+        thread_data.stats.table.probes += 1;
+        thread_data.stats.table.hits += candidate.is_some() as u64;
+
+        if let Some(score) = cutoff
         {
-            return Some(data.b);
+            thread_data.stats.table.cutoffs += 1;
+            return Some(score);
         }
 
-        let mut moves = super::PrioritizingMoveGenerator::new(&board, false).collect::<Vec<_>>();
+        // Only prune when our queen is not about to be surrounded.
+        let static_eval = (global_data.options.pruning && !Self::in_danger(&thread_data.board)).then(|| Self::static_eval(global_data, &thread_data.board));
+        let horizon_eval = static_eval.filter(|_| data.depth <= Self::FUTILITY_DEPTH);
 
-        // If we are stunlocked, we're probably dead.
-        if moves[0] == Move::Pass
-        {
-            return Some(MINIMUM_LOSS);
-        }
-        // Singular extensions.
-        else if moves.len() == 1
+        if let Some(eval) = horizon_eval
         {
-            data.depth += Depth::PLY;
-        }
+            // This close to the horizon, a position far above beta is unlikely to fall back into the window.
+            if eval - data.depth.scale(Self::FUTILITY_MARGIN) >= data.b
+            {
+                thread_data.stats.pruning.reverse += 1;
+                return Some(data.b);
+            }
 
-        // Try our table move first.
-        if let Some(table_move) = candidate
-        {
-            for i in 0..moves.len()
+            // And a position far below alpha can only be saved by tactics, which the quiescence search looks for.
+            if data.depth <= Depth::PLY && eval + data.depth.scale(Self::RAZOR_MARGIN) <= data.a
             {
-                if moves[i] == table_move
+                let q_data = ABData {
+                    a:     data.a,
+                    b:     data.a + 1,
+                    depth: Self::QUIESCENCE_DEPTH,
+                };
+
+                let v = Self::quiescence(global_data, thread_data, q_data)?;
+                if v <= data.a
                 {
-                    moves[0..i + 1].rotate_right(1);
-                    break;
+                    thread_data.stats.pruning.razored += 1;
+                    return Some(v);
                 }
             }
         }
 
+        // Null move observation holds?
+        if Self::bugzwang(global_data, thread_data, data.clone(), static_eval, prev)?
+        {
+            thread_data.stats.pruning.nulled += 1;
+            return Some(data.b);
+        }
+
+        // Quiet moves cannot lift a position this far below alpha back into the window.
+        let futile = horizon_eval.is_some_and(|eval| eval + data.depth.scale(Self::FUTILITY_MARGIN) <= data.a);
+
+        // The previous move has already been played, so its piece sits on its destination.
+        let ply = thread_data.ply();
+        let countered = prev.and_then(|mv| match mv
+        {
+            | Move::Move(piece, _) | Move::Place(piece, _) => thread_data.board.location(&piece).map(|to| (piece, to)),
+            | Move::Pass => None,
+        });
+
+        let key = thread_data.board.zobrist();
+        let mut picker = MovePicker::new(false)
+            .with_movements(global_data.cache.movements(key))
+            .with_table_move(candidate)
+            .with_killers(thread_data.killers.get(ply))
+            .with_countermove(countered.and_then(|(piece, to)| thread_data.counters.get(piece, to)));
+
         let mut best_score = MINIMUM_LOSS;
-        let mut best_mv = moves[0];
+        let mut best_mv = None;
         let mut null_window = false;
+        let mut searched = 0;
 
-        for mv in moves.iter()
+        while let Some(mv) = picker.next(&thread_data.board, Some(&thread_data.history))
         {
-            thread_data.play(mv);
+            // If we are stunlocked, we're probably dead.
+            if mv == Move::Pass
+            {
+                return Some(MINIMUM_LOSS);
+            }
+
+            let attack = MovePicker::is_attack(&thread_data.board, &mv);
+            let quiet = picker.stage() == Stage::Quiets && !attack;
 
-            let v = if null_window
+            if futile && quiet && searched > 0
+            {
+                thread_data.stats.pruning.futile += 1;
+                continue;
+            }
+
+            // A forced reply is looked past, and so are moves that fill a liberty of the enemy queen.
+            let single = searched == 0 && picker.is_single_reply(&thread_data.board);
+            let extension = match (single, attack)
+            {
+                | (true, _) => Depth::PLY,
+                | (false, true) => Self::QUEEN_THREAT_EXTENSION,
+                | (false, false) => Depth::NIL,
+            };
+            thread_data.stats.pruning.extended += (extension > Depth::NIL) as u64;
+
+            let depth = data.depth - Depth::PLY + extension;
+            let reduction = match static_eval.is_some() && quiet && data.depth >= Self::REDUCTION_DEPTH && searched >= Self::REDUCTION_MOVES
+            {
+                | true => Self::reduction(thread_data, &mv, data.depth, searched).min(depth - Depth::PLY),
+                | false => Depth::NIL,
+            };
+
+            thread_data.play(&mv);
+
+            // Late quiet moves are first searched at a reduced depth, and only searched fully if they beat alpha.
+            let reduced = if reduction > Depth::NIL
+            {
+                thread_data.stats.pruning.reduced += 1;
+
+                let reduced_data = ABData {
+                    a:     -data.a - 1,
+                    b:     -data.a,
+                    depth: depth - reduction,
+                };
+
+                let attempt = -Self::alpha_beta(global_data, thread_data, reduced_data, Some(mv))?;
+                thread_data.stats.pruning.researched += (attempt > data.a) as u64;
+                (attempt <= data.a).then_some(attempt)
+            }
+            else
+            {
+                None
+            };
+
+            let v = if let Some(v) = reduced
+            {
+                v
+            }
+            else if null_window
             {
                 let null_data = ABData {
                     a:     -data.a - 1,
                     b:     -data.a,
-                    depth: data.depth - Depth::PLY,
+                    depth,
                 };
 
-                let attempt = -Self::alpha_beta(global_data, thread_data, null_data, Some(*mv))?;
+                let attempt = -Self::alpha_beta(global_data, thread_data, null_data, Some(mv))?;
 
                 if (data.a + 1..=data.b - 1).contains(&attempt)
                 {
                     let next = ABData {
-                        a:     -data.b,
-                        b:     -attempt,
-                        depth: data.depth - Depth::PLY,
+                        a: -data.b,
+                        b: -attempt,
+                        depth,
                     };
 
-                    -Self::alpha_beta(global_data, thread_data, next, Some(*mv))?
+                    -Self::alpha_beta(global_data, thread_data, next, Some(mv))?
                 }
                 else
                 {
@@ -114,20 +247,21 @@ impl StrongestEvaluator
             else
             {
                 let next = ABData {
-                    a:     -data.b,
-                    b:     -data.a,
-                    depth: data.depth - Depth::PLY,
+                    a: -data.b,
+                    b: -data.a,
+                    depth,
                 };
 
-                -Self::alpha_beta(global_data, thread_data, next, Some(*mv))?
+                -Self::alpha_beta(global_data, thread_data, next, Some(mv))?
             };
 
-            thread_data.board = board.clone();
+            thread_data.undo();
+            searched += 1;
 
-            if v > best_score
+            if best_mv.is_none() || v > best_score
             {
                 best_score = v;
-                best_mv = *mv;
+                best_mv = Some(mv);
             }
 
             if v > data.a
@@ -138,16 +272,38 @@ impl StrongestEvaluator
 
             if data.a >= data.b
             {
-                let _p = prev;
+                thread_data.stats.cutoffs.record(searched - 1);
+
+                // Remember quiet moves that refute this position, so that siblings can try them early.
+                let quiet = match (mv, picker.stage())
+                {
+                    | (Move::Move(piece, _) | Move::Place(piece, _), Stage::Killers | Stage::Quiets) => thread_data.board.destination(&mv).map(|to| (piece, to)),
+                    | _ => None,
+                };
+
+                if let Some((piece, to)) = quiet
+                {
+                    thread_data.history.reward(piece, to, data.depth);
+                    thread_data.killers.store(ply, mv);
+
+                    if let Some((prev_piece, prev_to)) = countered
+                    {
+                        thread_data.counters.store(prev_piece, prev_to, mv);
+                    }
+                }
                 break;
             }
         }
 
-        thread_data.leaf_count -= 1;
-        thread_data.stem_count += 1;
+        if let Some(movements) = picker.generated_movements()
+        {
+            global_data.cache.store_movements(key, movements);
+        }
+
+        thread_data.stats.nodes.stems += 1;
 
         // Something is wrong if we're getting here with passes.
-        assert!(best_mv != Move::Pass);
+        let best_mv = best_mv.expect("searched a node without any moves");
 
         let entry = TTEntry {
             key:   thread_data.board.zobrist(),
@@ -161,92 +317,161 @@ impl StrongestEvaluator
         Some(scores::normalize(best_score))
     }
 
-    // Performs the aspiration loop until an exact score is found.
-    fn aspiration_search(global_data: &GlobalData, thread_data: &mut ThreadData, search_depth: Depth, window: Option<i32>) -> Option<()>
+    /// Searches the root inside a window around the score of the last iteration, which cuts off more of the tree while
+    /// the score holds steady. When the score falls out of the window, the window widens on that side and the root is
+    /// searched again, until the score lands inside it.
+    ///
+    /// Shallow iterations, skill levels that choose between several candidates, and decided games use the full window.
+    /// So does the first iteration a thread completes, since helper threads that skip depths have no score to centre on.
+    fn aspiration_search(global_data: &GlobalData, thread_data: &mut ThreadData, moves: &mut [ScoredMove], depth: Depth) -> Option<ScoredMove>
     {
-        let Some(window) = window
-        else
-        {
-            return Some(());
-        };
+        let target = thread_data.target;
+        let windowed = depth >= Self::ASPIRATION_DEPTH
+            && thread_data.completed > Depth::NIL
+            && global_data.options.skill().candidates() == 1
+            && scores::reconstruct(target).abs() != MINIMUM_WIN;
 
-        if search_depth < Depth::new(2)
+        if !windowed
         {
-            Some(())
+            thread_data.window = None;
+            return Self::reordering_search(global_data, thread_data, moves, depth, MINIMUM_LOSS, MINIMUM_WIN);
         }
-        else
+
+        let mut delta = Self::ASPIRATION_WINDOW;
+        let mut a = target.saturating_sub(delta).max(MINIMUM_LOSS);
+        let mut b = target.saturating_add(delta).min(MINIMUM_WIN);
+        thread_data.stats.aspiration.windows += 1;
+        thread_data.window = Some([a, b]);
+
+        loop
         {
-            let target_score = thread_data.target;
-            let search_data = ABData {
-                a:     target_score.saturating_sub(window).max(MINIMUM_LOSS),
-                b:     target_score.saturating_add(window).min(MINIMUM_WIN),
-                depth: search_depth,
-            };
+            let best = Self::reordering_search(global_data, thread_data, moves, depth, a, b)?;
+            delta = delta.saturating_mul(2);
 
-            Self::alpha_beta(global_data, thread_data, search_data, None)?;
-            Some(())
+            if best.score <= a && a > MINIMUM_LOSS
+            {
+                thread_data.stats.aspiration.fail_lows += 1;
+                a = best.score.saturating_sub(delta).max(MINIMUM_LOSS);
+            }
+            else if best.score >= b && b < MINIMUM_WIN
+            {
+                thread_data.stats.aspiration.fail_highs += 1;
+                b = best.score.saturating_add(delta).min(MINIMUM_WIN);
+            }
+            else
+            {
+                return Some(best);
+            }
         }
     }
 
-    // Looks for the null move observation.
-    fn bugzwang(global_data: &GlobalData, thread_data: &mut ThreadData, search_data: ABData) -> Option<i32>
+    /// Looks for the null move observation: if the position stays above beta even after passing, then some real move
+    /// will surely keep it there, so the node can be cut off after a shallow search.
+    ///
+    /// Passing is never good in Hive, except when every move would loosen the hive around a queen or free an enemy
+    /// piece. So there is no null move next to a nearly surrounded queen, nor when the player to move has no pieces in
+    /// hand and few free pieces left, which is where such zugzwangs come from.
+    fn bugzwang(
+        global_data: &GlobalData,
+        thread_data: &mut ThreadData,
+        search_data: ABData,
+        static_eval: Option<i32>,
+        prev: Option<Move>,
+    ) -> Option<bool>
     {
-        const DEPTH_REDUCTION: Depth = Depth::new(2);
-
         let data = search_data;
-        let board = thread_data.board.clone();
-        let movegen = super::PrioritizingMoveGenerator::new(&board, false).collect::<Vec<_>>();
+        let board = &thread_data.board;
 
-        if movegen.contains(&Move::Pass)
+        let above_beta = static_eval.is_some_and(|eval| eval >= data.b);
+        if !above_beta || data.depth < Self::NULL_DEPTH || prev == Some(Move::Pass)
         {
-            if data.depth > DEPTH_REDUCTION && Self::evaluate_board(&board) >= data.b
-            {
-                let next_data = ABData {
-                    a:     -data.b,
-                    b:     -data.b + 1,
-                    depth: data.depth - DEPTH_REDUCTION,
-                };
+            return Some(false);
+        }
 
-                thread_data.play(&Move::Pass);
-                let v = -Self::alpha_beta(global_data, thread_data, next_data, None)?;
-                thread_data.board = board.clone();
+        if Self::zugzwang_prone(board)
+        {
+            thread_data.stats.pruning.zugzwang += 1;
+            return Some(false);
+        }
 
-                if v >= data.b
-                {
-                    return Some(v);
-                }
-            }
+        // Deeper nodes can afford to look less far after passing.
+        let reduction = Self::NULL_REDUCTION + data.depth / 4;
+        let next_data = ABData {
+            a:     -data.b,
+            b:     -data.b + 1,
+            depth: (data.depth - Depth::PLY - reduction).max(Depth::NIL),
+        };
+
+        thread_data.stats.nodes.null += 1;
+        thread_data.play(&Move::Pass);
+        let v = -Self::alpha_beta(global_data, thread_data, next_data, Some(Move::Pass))?;
+        thread_data.undo();
+
+        // A win found after passing could have come from the pass itself, so it only proves the cutoff.
+        Some(v >= data.b)
+    }
+
+    /// Whether passing may be the best move for the player to move, so that the null move observation does not hold.
+    fn zugzwang_prone(board: &Board) -> bool
+    {
+        let player = board.to_move();
+        let mut queens = [Player::White, Player::Black].into_iter().filter_map(|player| board.queen(player));
+        if queens.any(|queen| board.field().neighbours(queen).len() >= Self::DANGER_KILLSPOTS)
+        {
+            return true;
         }
 
-        Some(MINIMUM_LOSS)
+        let in_hand = board.pouch().hand(player).iter().map(|n| *n as usize).sum::<usize>();
+        let placed = board.pouch().extents().iter().map(|n| *n as usize).sum::<usize>() - in_hand;
+        let free = placed - board.pinned_pieces(player).len();
+
+        in_hand == 0 && free < Self::NULL_FREE_PIECES
     }
 
-    /// Performs the main iterative deepening loop.
-    pub(super) fn iterative_search(global_data: &GlobalData, thread_data: &mut ThreadData)
+    /// Whether the player to move has lost so many queen liberties that pruning around the queen is unsafe.
+    fn in_danger(board: &Board) -> bool
+    {
+        board.queen(board.to_move()).is_some_and(|queen| board.field().neighbours(queen).len() >= Self::DANGER_KILLSPOTS)
+    }
+
+    /// How far to reduce a late quiet move, which grows with the depth and with how late the move comes, and shrinks
+    /// for moves that have often caused cutoffs before.
+    fn reduction(thread_data: &ThreadData, mv: &Move, depth: Depth, index: usize) -> Depth
     {
-        // We vary the starting depth of each thread a bit, so that we can cover different portions of the
-        // game tree. Some work done early by deeper threads prepares the transposition table for the threads
-        // that start closer to the real position.
-        const DEPTH_VARIANCE_BY_THREAD: i32 = 4;
+        let base = 50.0 + 100.0 * (depth.floor() as f64).ln() * (index as f64).ln() / 2.0;
+
+        let history = match (mv, thread_data.board.destination(mv))
+        {
+            | (Move::Move(piece, _) | Move::Place(piece, _), Some(to)) => thread_data.history.score(*piece, to),
+            | _ => 0,
+        };
+        let bonus = 100 * history / HistoryTable::MAX;
 
-        let search_range = Depth::new(1) + thread_data.id as i32 % DEPTH_VARIANCE_BY_THREAD..=global_data.args.depth();
+        Depth::raw((base as i32 - bonus).max(0))
+    }
 
+    /// Performs the main iterative deepening loop.
+    ///
+    /// Every thread but the first skips some depths, so that the threads spread over neighbouring depths instead of
+    /// all searching the same tree. Work done early by deeper threads prepares the transposition table for the others.
+    pub(super) fn iterative_search(global_data: &GlobalData, thread_data: &mut ThreadData)
+    {
         // Get the root moves so we can reorder them.
         let board = thread_data.board.clone();
         let mut moves = super::PrioritizingMoveGenerator::new(&board, true)
             .map(|mv| ScoredMove { mv, score: 0 })
             .collect::<Vec<_>>();
 
-        for search_depth in search_range
+        for search_depth in Depth::new(1)..=global_data.depth_limit()
         {
-            // Try our window search first.
-            if Self::aspiration_search(global_data, thread_data, search_depth, ABData::ASPIRATION_WINDOW).is_none()
+            if Self::skip_depth(thread_data.id, search_depth, board.turn())
             {
-                break;
+                continue;
             }
 
             // Conduct a search from the root, reordering moves in greatest-score-order while doing so.
-            if Self::reordering_search(global_data, thread_data, &mut moves, search_depth).is_none()
+            let Some(best) = Self::aspiration_search(global_data, thread_data, &mut moves, search_depth)
+            else
             {
                 break;
             };
@@ -257,24 +482,40 @@ impl StrongestEvaluator
                 global_data.max_depth.store(search_depth.floor() as u64, Ordering::SeqCst);
             }
 
-            // Check the root result. If it's a win score, we just abort early.
-            let hit = global_data.transpositions.load(board.zobrist()).unwrap();
-
-            // Remember this result.
-            thread_data.target = hit.score;
-            thread_data.best_move = hit.mv.into();
+            // Remember this thread's own result, which the other threads may have overwritten in the table.
+            thread_data.completed = search_depth;
+            thread_data.iterations.push(IterationStats {
+                depth:   search_depth.floor(),
+                nodes:   thread_data.stats.nodes.total(),
+                time_ms: global_data.start_time.elapsed().as_secs_f64() * 1e+3,
+                score:   best.score,
+                window:  thread_data.window,
+            });
+            thread_data.target = best.score;
+            thread_data.best_move = Some(best.mv);
+            thread_data.root_moves = moves.clone();
 
             // Load the principal variation scores from the table.
             global_data.transpositions.get_principal_variation(&board, &mut thread_data.variation);
 
-            if scores::reconstruct(hit.score).abs() == MINIMUM_WIN
+            // If it's a win score, we just abort early.
+            if scores::reconstruct(best.score).abs() == MINIMUM_WIN
             {
+                global_data.signal();
                 break;
             }
         }
+
+        // The first thread to finish the deepest search ends the search for everyone.
+        if thread_data.completed == global_data.depth_limit()
+        {
+            global_data.signal();
+        }
     }
 
-    // Computes exciting extensions at leaves to ensure we don't miss tactical resolutions due to the horizon effect.
+    // Resolves the queen tactics at leaves, so that the horizon does not hide a surround that is about to happen.
+    //
+    // The player to move can always stand pat on the static evaluation instead of playing a tactical move.
     fn quiescence(global_data: &GlobalData, thread_data: &mut ThreadData, search_data: ABData) -> Option<i32>
     {
         if global_data.should_stop()
@@ -283,21 +524,29 @@ impl StrongestEvaluator
         }
 
         let mut data = search_data;
+        thread_data.stats.nodes.quiescence += 1;
 
+        let stand_pat = Self::static_eval(global_data, &thread_data.board);
         if data.depth <= Depth::NIL || matches!(thread_data.board.state(), GameState::WhiteWins | GameState::BlackWins | GameState::Draw)
         {
-            return Some(Self::evaluate_board(&thread_data.board));
+            return Some(stand_pat);
         }
 
-        let board = thread_data.board.clone();
-        let moves = board.generate_tactical_moves();
-        let mut best_score = MINIMUM_LOSS;
+        if stand_pat >= data.b
+        {
+            return Some(stand_pat);
+        }
 
-        if moves.is_empty()
+        // Not even the best tactic would bring the position back up to alpha.
+        if Self::delta_prunes(&thread_data.board, stand_pat, data.a)
         {
-            return Some(Self::evaluate_board(&board));
+            return Some(stand_pat);
         }
 
+        let moves = Self::ordered_tactics(&thread_data.board);
+        let mut best_score = stand_pat;
+        data.a = data.a.max(stand_pat);
+
         for mv in moves.iter()
         {
             let next_data = ABData {
@@ -308,7 +557,7 @@ impl StrongestEvaluator
 
             thread_data.play(mv);
             let v = -Self::quiescence(global_data, thread_data, next_data)?;
-            thread_data.board = board.clone();
+            thread_data.undo();
 
             best_score = best_score.max(v);
             data.a = data.a.max(v);
@@ -322,24 +571,129 @@ impl StrongestEvaluator
         Some(best_score)
     }
 
-    /// Searches through the moves, reordering them by their evaluation.
-    fn reordering_search(global_data: &GlobalData, thread_data: &mut ThreadData, moves: &mut [ScoredMove], depth: Depth) -> Option<()>
+    /// Whether the quiescence search can stand pat without trying any tactics, because the static evaluation is so far
+    /// below alpha that filling one more enemy liberty could not make up the difference.
+    ///
+    /// A queen with one liberty left can still be surrounded, which wins outright, and a queen in danger needs every
+    /// defence looked at, so neither is pruned.
+    fn delta_prunes(board: &Board, stand_pat: i32, alpha: i32) -> bool
     {
-        let mut data = ABData {
+        let enemy_liberties = board.queen(board.to_move().flip()).map(|queen| 6 - board.field().neighbours(queen).len());
+
+        stand_pat + Self::DELTA_MARGIN < alpha && enemy_liberties.is_some_and(|liberties| liberties > 1) && !Self::in_danger(board)
+    }
+
+    /// Gets the tactical moves in a position, with those that fill a liberty of the enemy queen first.
+    fn ordered_tactics(board: &Board) -> Vec<Move>
+    {
+        let mut moves = board.generate_tactical_moves();
+        moves.sort_by_key(|mv| !MovePicker::is_attack(board, mv));
+        moves
+    }
+
+    /// Finds the quiet position whose static evaluation the quiescence search would return from a position, so that
+    /// it can be evaluated again with other weights without repeating the search.
+    pub(super) fn quiet_position(params: &EvalParams, board: &Board) -> Board
+    {
+        let data = ABData {
             a:     MINIMUM_LOSS,
             b:     MINIMUM_WIN,
+            depth: Self::QUIESCENCE_DEPTH,
+        };
+
+        Self::resolve(params, &mut board.clone(), data).1
+    }
+
+    // Follows the same tactical moves as the quiescence search, keeping the position at the end of the best line.
+    fn resolve(params: &EvalParams, board: &mut Board, search_data: ABData) -> (i32, Board)
+    {
+        let mut data = search_data;
+
+        let stand_pat = Self::evaluate_board(params, board);
+        let mut best = (stand_pat, board.clone());
+
+        if data.depth <= Depth::NIL || board.state() != GameState::InProgress || stand_pat >= data.b || Self::delta_prunes(board, stand_pat, data.a)
+        {
+            return best;
+        }
+
+        let moves = Self::ordered_tactics(board);
+        data.a = data.a.max(stand_pat);
+
+        for mv in moves.iter()
+        {
+            let next_data = ABData {
+                a:     -data.b,
+                b:     -data.a,
+                depth: data.depth - Depth::PLY,
+            };
+
+            board.play_unchecked(mv);
+            let (v, leaf) = Self::resolve(params, board, next_data);
+            board.undo_one().expect("undid a move the search never played");
+
+            if -v > best.0
+            {
+                best = (-v, leaf);
+            }
+            data.a = data.a.max(-v);
+
+            if data.a >= data.b
+            {
+                break;
+            }
+        }
+
+        best
+    }
+
+    /// Searches through the moves inside the window, reordering them by their evaluation.
+    ///
+    /// Only the best move is scored exactly, unless the skill level asks for more candidates to choose between; the rest
+    /// only get upper bounds. A move that reaches the top of the window ends the search, as the window was too low.
+    fn reordering_search(
+        global_data: &GlobalData,
+        thread_data: &mut ThreadData,
+        moves: &mut [ScoredMove],
+        depth: Depth,
+        a: i32,
+        b: i32,
+    ) -> Option<ScoredMove>
+    {
+        let mut data = ABData {
+            a,
+            b,
             depth: depth - Depth::PLY,
         };
 
-        let board = thread_data.board.clone();
+        let candidates = global_data.options.skill().candidates();
+        let mut best_scores = Vec::with_capacity(candidates + 1);
 
         for mv in moves.iter_mut()
         {
+            let child_data = ABData {
+                a:     -data.b,
+                b:     -data.a,
+                depth: data.depth,
+            };
+
             thread_data.play(&mv.mv);
-            mv.score = -Self::alpha_beta(global_data, thread_data, data.clone(), Some(mv.mv.clone()))?;
-            thread_data.board = board.clone();
+            mv.score = -Self::alpha_beta(global_data, thread_data, child_data, Some(mv.mv))?;
+            thread_data.undo();
+
+            // Any move that cannot beat the weakest candidate so far only needs a bound.
+            best_scores.push(mv.score);
+            best_scores.sort_by_key(|score| -score);
+            best_scores.truncate(candidates);
+            if best_scores.len() == candidates
+            {
+                data.a = data.a.max(best_scores[candidates - 1]);
+            }
 
-            data.a = data.a.max(mv.score);
+            if mv.score >= data.b
+            {
+                break;
+            }
         }
 
         // Put the strongest moves at the front.
@@ -351,11 +705,29 @@ impl StrongestEvaluator
             mv: mv.into(),
             score,
             depth,
-            age: TTAge::compute(score, data.a, data.b),
+            age: TTAge::compute(score, a, b),
         };
 
         global_data.transpositions.store(&entry);
-        Some(())
+        Some(moves[0])
+    }
+
+    /// Whether a helper thread should skip an iteration, so that threads are spread over the depths being searched.
+    ///
+    /// Helpers are split into groups that skip every 1, 2, 3 or 4 depths, each with its own phase. The move number is
+    /// part of the phase so that a thread does not skip the same depths every turn.
+    fn skip_depth(thread_id: usize, depth: Depth, turn: u8) -> bool
+    {
+        const SKIP_SIZE: [i32; 20] = [1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3, 4, 4, 4, 4, 4, 4, 4, 4];
+        const SKIP_PHASE: [i32; 20] = [0, 1, 0, 1, 2, 3, 0, 1, 2, 3, 4, 5, 0, 1, 2, 3, 4, 5, 6, 7];
+
+        if thread_id == 0
+        {
+            return false;
+        }
+
+        let i = (thread_id - 1) % SKIP_SIZE.len();
+        (depth.floor() + turn as i32 + SKIP_PHASE[i]) / SKIP_SIZE[i] % 2 == 1
     }
 }
 
@@ -379,9 +751,3 @@ impl Default for ABData
         }
     }
 }
-
-impl ABData
-{
-    // The default radius of the aspiration window.
-    pub const ASPIRATION_WINDOW: Option<i32> = Some(50);
-}
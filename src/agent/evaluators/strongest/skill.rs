@@ -0,0 +1,109 @@
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
+
+use crate::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// A handicap on the search, from 0 for the weakest play to [`Skill::MAX`] for full strength.
+///
+/// Weaker levels search less deeply and visit fewer nodes, blur the scores of the best moves with noise, and sometimes
+/// choose between the best few moves at random, favouring the better ones.
+pub struct Skill(u8);
+
+impl Skill
+{
+    /// The skill level at full strength, which has no handicap at all.
+    pub const MAX: u8 = 20;
+
+    /// The noise added to root scores for each level of handicap, in centipawns either way.
+    const NOISE_PER_LEVEL: i32 = 10;
+
+    /// The nodes searched at level 0, which doubles every two levels.
+    const BASE_NODES: u64 = 250;
+
+    /// The temperature of the softmax over the candidate moves at level 0, in centipawns.
+    const TEMPERATURE: f64 = 200.0;
+
+    /// Creates a skill level, capping it at [`Skill::MAX`].
+    pub fn new(level: u8) -> Skill
+    {
+        Skill(level.min(Self::MAX))
+    }
+
+    /// The number of best root moves that are scored exactly, for the handicap to choose between.
+    pub fn candidates(&self) -> usize
+    {
+        1 + self.handicap() as usize / 4
+    }
+
+    /// The deepest the search may go.
+    pub fn depth(&self) -> Depth
+    {
+        match self.is_limited()
+        {
+            | true => Depth::new(1 + self.0 as i32 / 3),
+            | false => Depth::MAX,
+        }
+    }
+
+    /// Whether this level plays below full strength.
+    pub fn is_limited(&self) -> bool
+    {
+        self.0 < Self::MAX
+    }
+
+    /// The skill level.
+    pub fn level(&self) -> u8
+    {
+        self.0
+    }
+
+    /// The most nodes the search may visit.
+    pub fn nodes(&self) -> u64
+    {
+        match self.is_limited()
+        {
+            | true => Self::BASE_NODES << (self.0 / 2),
+            | false => u64::MAX,
+        }
+    }
+
+    /// Chooses between root moves sorted from best to worst, of which at least the first [`Skill::candidates`] have
+    /// exact scores.
+    ///
+    /// Each candidate's score is blurred by noise. Usually the best blurred score wins, but the weaker the level, the
+    /// more often the move is instead drawn from a softmax over the blurred scores.
+    pub fn pick<R: Rng>(&self, moves: &[ScoredMove], rng: &mut R) -> Option<ScoredMove>
+    {
+        let candidates = &moves[..moves.len().min(self.candidates())];
+        if candidates.len() < 2
+        {
+            return candidates.first().copied();
+        }
+
+        let noise = Self::NOISE_PER_LEVEL * self.handicap() as i32;
+        let blurred = candidates.iter().map(|sm| sm.score.saturating_add(rng.gen_range(-noise..=noise))).collect::<Vec<i32>>();
+        let best = *blurred.iter().max().unwrap();
+
+        let index = if rng.gen_bool(self.handicap() as f64 / Self::MAX as f64)
+        {
+            let temperature = Self::TEMPERATURE * self.handicap() as f64 / Self::MAX as f64;
+            let weights = blurred.iter().map(|score| ((score - best) as f64 / temperature).exp());
+            WeightedIndex::new(weights).map_or(0, |dist| dist.sample(rng))
+        }
+        else
+        {
+            blurred.iter().position(|score| *score == best).unwrap()
+        };
+
+        Some(candidates[index])
+    }
+}
+
+impl Skill
+{
+    /// How far below full strength this level is.
+    fn handicap(&self) -> u8
+    {
+        Self::MAX - self.0
+    }
+}
@@ -0,0 +1,217 @@
+use std::ops::AddAssign;
+
+use serde::Serialize;
+
+#[derive(Clone, Debug, Default, Serialize)]
+/// Node counts by the kind of search that visited them.
+pub struct NodeStats
+{
+    /// Nodes entered by the main alpha-beta search.
+    pub main:       u64,
+    /// Main nodes whose moves were searched, rather than being cut off before that.
+    pub stems:      u64,
+    /// Nodes entered by the quiescence search.
+    pub quiescence: u64,
+    /// Null-move searches started.
+    pub null:       u64,
+}
+
+impl NodeStats
+{
+    /// Main nodes that were not expanded.
+    pub fn leaves(&self) -> u64
+    {
+        self.main - self.stems
+    }
+
+    /// Every node visited, of any kind.
+    pub fn total(&self) -> u64
+    {
+        self.main + self.quiescence
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+/// How useful the transposition table was.
+pub struct TableStats
+{
+    pub probes:  u64,
+    pub hits:    u64,
+    /// Hits that ended the search of a node without searching any moves.
+    pub cutoffs: u64,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+/// Beta cutoffs, by the index of the move that caused them.
+pub struct CutoffStats
+{
+    pub total:    u64,
+    /// The last bucket counts every move from that index on.
+    pub by_index: [u64; 8],
+}
+
+impl CutoffStats
+{
+    /// The percentage of cutoffs caused by the first move searched.
+    pub fn first_move_rate(&self) -> f64
+    {
+        if self.total == 0
+        {
+            0.0
+        }
+        else
+        {
+            100.0 * self.by_index[0] as f64 / self.total as f64
+        }
+    }
+
+    /// Records a cutoff by the move at the given index.
+    pub fn record(&mut self, index: usize)
+    {
+        self.total += 1;
+        self.by_index[index.min(self.by_index.len() - 1)] += 1;
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+/// How often the selective parts of the search changed the depth of a node or skipped it.
+pub struct PruningStats
+{
+    /// Late moves searched at a reduced depth.
+    pub reduced:    u64,
+    /// Reduced moves that beat alpha and had to be searched again at full depth.
+    pub researched: u64,
+    /// Quiet moves skipped near the horizon because the position was too far below alpha.
+    pub futile:     u64,
+    /// Nodes cut off near the horizon because the position was too far above beta.
+    pub reverse:    u64,
+    /// Nodes near the horizon that were settled by the quiescence search alone.
+    pub razored:    u64,
+    /// Moves extended for being the only reply, or for filling a liberty of the enemy queen.
+    pub extended:   u64,
+    /// Nodes cut off because the position stayed above beta even after passing.
+    pub nulled:     u64,
+    /// Nodes where the null move would have been tried, but passing might have been the best move.
+    pub zugzwang:   u64,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+/// How often the root score fell outside the aspiration window, so that the root had to be searched again.
+pub struct AspirationStats
+{
+    /// Iterations searched inside an aspiration window.
+    pub windows:    u64,
+    /// Re-searches after the score fell below the window.
+    pub fail_lows:  u64,
+    /// Re-searches after the score rose above the window.
+    pub fail_highs: u64,
+}
+
+impl AspirationStats
+{
+    /// Every re-search of the root, on either side of the window.
+    pub fn researches(&self) -> u64
+    {
+        self.fail_lows + self.fail_highs
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+/// When one iteration of the iterative deepening loop finished, and what it found.
+pub struct IterationStats
+{
+    pub depth:   i32,
+    /// Nodes visited by the thread since the start of the search.
+    pub nodes:   u64,
+    /// Milliseconds since the start of the search.
+    pub time_ms: f64,
+    pub score:   i32,
+    /// The first aspiration window the iteration searched inside, if it did not search the full window.
+    pub window:  Option<[i32; 2]>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+/// Counters kept by each search thread, which can be summed over threads.
+pub struct SearchStats
+{
+    pub nodes:      NodeStats,
+    pub table:      TableStats,
+    pub cutoffs:    CutoffStats,
+    pub pruning:    PruningStats,
+    pub aspiration: AspirationStats,
+}
+
+impl AddAssign<&SearchStats> for SearchStats
+{
+    fn add_assign(&mut self, other: &SearchStats)
+    {
+        self.nodes.main += other.nodes.main;
+        self.nodes.stems += other.nodes.stems;
+        self.nodes.quiescence += other.nodes.quiescence;
+        self.nodes.null += other.nodes.null;
+
+        self.table.probes += other.table.probes;
+        self.table.hits += other.table.hits;
+        self.table.cutoffs += other.table.cutoffs;
+
+        self.cutoffs.total += other.cutoffs.total;
+        for (mine, theirs) in self.cutoffs.by_index.iter_mut().zip(other.cutoffs.by_index)
+        {
+            *mine += theirs;
+        }
+
+        self.pruning.reduced += other.pruning.reduced;
+        self.pruning.researched += other.pruning.researched;
+        self.pruning.futile += other.pruning.futile;
+        self.pruning.reverse += other.pruning.reverse;
+        self.pruning.razored += other.pruning.razored;
+        self.pruning.extended += other.pruning.extended;
+        self.pruning.nulled += other.pruning.nulled;
+        self.pruning.zugzwang += other.pruning.zugzwang;
+
+        self.aspiration.windows += other.aspiration.windows;
+        self.aspiration.fail_lows += other.aspiration.fail_lows;
+        self.aspiration.fail_highs += other.aspiration.fail_highs;
+    }
+}
+
+/// The effective branching factor between the last two iterations that searched consecutive depths, if there are any.
+pub fn branching_factor(iterations: &[IterationStats]) -> Option<f64>
+{
+    let per_iteration = iterations
+        .iter()
+        .scan(0, |before, iteration| {
+            let nodes = iteration.nodes - *before;
+            *before = iteration.nodes;
+            Some((iteration.depth, nodes))
+        })
+        .collect::<Vec<(i32, u64)>>();
+
+    per_iteration
+        .windows(2)
+        .rev()
+        .find(|w| w[0].0 + 1 == w[1].0 && w[0].1 > 0)
+        .map(|w| w[1].1 as f64 / w[0].1 as f64)
+}
+
+#[derive(Clone, Debug, Serialize)]
+/// Everything recorded about a single search, as written to the telemetry file.
+pub struct SearchRecord
+{
+    pub position:         String,
+    pub mv:               String,
+    pub score:            i32,
+    /// The principal variation of the thread whose move was played, as read back from the table.
+    pub pv:               Vec<String>,
+    pub depth:            i32,
+    pub threads:          usize,
+    pub time_ms:          f64,
+    /// Permille of the transposition table in use.
+    pub hashfull:         usize,
+    /// Computed from the iterations of the thread whose move was played.
+    pub branching_factor: Option<f64>,
+    #[serde(flatten)]
+    /// Summed over every thread.
+    pub stats:            SearchStats,
+    pub iterations:       Vec<IterationStats>,
+}
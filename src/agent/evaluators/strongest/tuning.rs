@@ -0,0 +1,172 @@
+use std::thread;
+
+use super::*;
+
+/// The smallest step, as a fraction of a weight, that the local search tries before it stops.
+const MIN_STEP: f64 = 1.0 / 64.0;
+
+/// Keeps the loss finite when a position is scored as a certain result that did not happen.
+const EPSILON: f64 = 1e-9;
+
+#[derive(Clone, Debug)]
+/// Fits the evaluation weights to the results of games, Texel-style.
+///
+/// Each position is resolved once to the quiet position that the quiescence search would evaluate, and the weights are
+/// then fitted so that a logistic function of the quiet position's evaluation predicts the result of the game.
+pub struct Tuner
+{
+    /// Quiet positions, with the result of their game from the perspective of the player to move.
+    positions: Vec<(Board, f64)>,
+    /// Scales evaluations into the logistic function.
+    pub scale: f64,
+    threads:   usize,
+}
+
+impl Tuner
+{
+    /// Resolves the positions of each game, from the given turn on, using the given weights to choose the quiet
+    /// positions. Positions that resolve to the end of a game are left out, since their evaluation does not depend on
+    /// the weights.
+    pub fn new(params: &EvalParams, games: &[GameRecord], skip: u8, threads: usize) -> Tuner
+    {
+        let labelled = games
+            .iter()
+            .flat_map(|game| game.positions(skip).into_iter().map(|board| (board, game.result)))
+            .collect::<Vec<_>>();
+
+        let positions = Self::map(&labelled, threads, |(board, result)| {
+            let quiet = StrongestEvaluator::quiet_position(params, board);
+            let result = if quiet.to_move() == Player::White { *result } else { 1.0 - result };
+            (quiet, result)
+        })
+        .into_iter()
+        .filter(|(board, _)| board.state() == GameState::InProgress)
+        .collect();
+
+        Tuner {
+            positions,
+            scale: 1.0 / 100.0,
+            threads: threads.max(1),
+        }
+    }
+
+    /// Chooses the scale that minimizes the loss for the given weights, so that the weights are fitted against the
+    /// scale they already imply.
+    pub fn fit_scale(&mut self, params: &EvalParams) -> f64
+    {
+        let evaluations = self.evaluations(params);
+
+        // Scan the scale by factors of about 1.05 between 1/10000 and 1.
+        self.scale = (0..=200)
+            .map(|i| 10f64.powf(-4.0 + i as f64 / 50.0))
+            .min_by(|a, b| self.loss_of(&evaluations, *a).total_cmp(&self.loss_of(&evaluations, *b)))
+            .unwrap();
+
+        self.scale
+    }
+
+    /// The number of quiet positions being fitted.
+    pub fn len(&self) -> usize
+    {
+        self.positions.len()
+    }
+
+    /// Whether there are no positions to fit.
+    pub fn is_empty(&self) -> bool
+    {
+        self.positions.is_empty()
+    }
+
+    /// The mean logistic loss of predicting each game's result from its evaluations with the given weights.
+    pub fn loss(&self, params: &EvalParams) -> f64
+    {
+        self.loss_of(&self.evaluations(params), self.scale)
+    }
+
+    /// Fits the weights with a local search, moving one weight at a time by a fraction of its size, and halving that
+    /// fraction whenever no move helps. Calls back with the loss and the weights after each round.
+    pub fn tune(&self, params: &EvalParams, rounds: usize, mut each: impl FnMut(usize, f64, &EvalParams)) -> EvalParams
+    {
+        let mut best = params.clone();
+        let mut best_loss = self.loss(&best);
+        let mut step = 0.25;
+
+        for round in 0..rounds
+        {
+            let mut improved = false;
+
+            for i in 0..EvalParams::NAMES.len()
+            {
+                let delta = step * best.values()[i].abs().max(1.0);
+
+                for sign in [1.0, -1.0]
+                {
+                    let mut candidate = best.clone();
+                    *candidate.values_mut()[i] += sign * delta;
+
+                    let loss = self.loss(&candidate);
+                    if loss < best_loss
+                    {
+                        best = candidate;
+                        best_loss = loss;
+                        improved = true;
+                        break;
+                    }
+                }
+            }
+
+            each(round, best_loss, &best);
+
+            if !improved
+            {
+                step /= 2.0;
+                if step < MIN_STEP
+                {
+                    break;
+                }
+            }
+        }
+
+        best
+    }
+}
+
+impl Tuner
+{
+    /// Evaluates every position with the given weights.
+    fn evaluations(&self, params: &EvalParams) -> Vec<i32>
+    {
+        Self::map(&self.positions, self.threads, |(board, _)| StrongestEvaluator::evaluate_board(params, board))
+    }
+
+    /// The mean logistic loss of the given evaluations at the given scale.
+    fn loss_of(&self, evaluations: &[i32], scale: f64) -> f64
+    {
+        let total = self
+            .positions
+            .iter()
+            .zip(evaluations)
+            .map(|((_, result), eval)| {
+                let p = (1.0 / (1.0 + (-scale * *eval as f64).exp())).clamp(EPSILON, 1.0 - EPSILON);
+                -(result * p.ln() + (1.0 - result) * (1.0 - p).ln())
+            })
+            .sum::<f64>();
+
+        total / evaluations.len().max(1) as f64
+    }
+
+    /// Maps a function over the items, splitting them evenly between threads.
+    fn map<T: Sync, U: Send>(items: &[T], threads: usize, f: impl Fn(&T) -> U + Sync) -> Vec<U>
+    {
+        let chunk = items.len().div_ceil(threads.max(1)).max(1);
+
+        thread::scope(|s| {
+            let handles = items
+                .chunks(chunk)
+                .map(|chunk| s.spawn(|| chunk.iter().map(&f).collect::<Vec<U>>()))
+                .collect::<Vec<_>>();
+
+            handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+        })
+    }
+}
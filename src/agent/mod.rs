@@ -1,30 +1,85 @@
 use crate::prelude::*;
 
+pub mod arena;
+pub mod bench;
+pub mod cache;
+pub mod corpus;
 pub mod evaluators;
 pub mod scalars;
 pub mod searchargs;
 pub mod table;
+pub mod telemetry;
 pub mod variation;
 
+pub use cache::*;
+pub use corpus::*;
 pub use scalars::*;
 pub use searchargs::*;
 pub use table::*;
+pub use telemetry::*;
 pub use variation::*;
 
 /// A trait representing a collection of policies by which we can evaluate a board position and find the best continuations.
 pub trait Evaluator
 {
-    type Generator: MoveGenerator;
+    type Generator<'a>: MoveGenerator;
 
     /// Returns the best move in the current position.
     fn best_move(&mut self, board: &Board, args: SearchArgs) -> Move;
 
+    /// Forgets everything learned from earlier searches, including any cached positions.
+    fn clear(&mut self) {}
+
+    /// Returns the deepest iteration completed by the most recent search, for evaluators that search by depth.
+    fn depth(&self) -> Depth
+    {
+        Depth::NIL
+    }
+
     /// Generates all valid moves on the given board.
     /// For performance reasons, this should be as lazy as possible!
-    fn generate_moves(board: &Board) -> Self::Generator;
+    fn generate_moves(board: &Board) -> Self::Generator<'_>;
+
+    /// Returns the memory in bytes used by the evaluator's tables, for evaluators that keep them.
+    fn memory(&self) -> usize
+    {
+        0
+    }
 
     /// Returns a new evaluator. Evaluators should be instanced so that they can support internal state.
     fn new(options: UhpOptions) -> Self;
+
+    /// Prepares for a new game, forgetting search results that will not be relevant to it.
+    fn new_game(&mut self) {}
+
+    /// Returns the name of the evaluator, as reported by the `info` command.
+    fn name(&self) -> &'static str
+    where
+        Self: Sized,
+    {
+        Error::type_name::<Self>()
+    }
+
+    /// Returns the number of nodes visited by the most recent search, for evaluators that count them.
+    fn nodes(&self) -> u64
+    {
+        0
+    }
+
+    /// Restores search results saved by [`Evaluator::save_table`], for evaluators that keep them.
+    fn load_table(&mut self, _path: &std::path::Path) -> Result<usize>
+    {
+        Err(Error::new(Kind::UnrecognizedCommand, "This evaluator has no table to load.".into()))
+    }
+
+    /// Saves the evaluator's search results to a file, for evaluators that keep them.
+    fn save_table(&self, _path: &std::path::Path) -> Result<usize>
+    {
+        Err(Error::new(Kind::UnrecognizedCommand, "This evaluator has no table to save.".into()))
+    }
+
+    /// Applies changed options, such as the memory available to the evaluator's tables.
+    fn set_options(&mut self, _options: &UhpOptions) {}
 }
 
 /// A trait alias that represents a forward iterator on a collection of moves.
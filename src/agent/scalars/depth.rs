@@ -92,6 +92,12 @@ impl Depth
         Depth(x - x % Self::PER_PLY.0)
     }
 
+    /// Scales an amount per ply by this depth, keeping any fraction of a ply.
+    pub const fn scale(&self, per_ply: i32) -> i32
+    {
+        self.0 * per_ply / Self::PER_PLY.0
+    }
+
     /// Returns the square of the depth.
     pub const fn squared(&self) -> i32
     {
@@ -247,19 +253,17 @@ impl std::iter::Step for Depth
         }
     }
 
-    fn steps_between(start: &Self, end: &Self) -> Option<usize>
+    fn steps_between(start: &Self, end: &Self) -> (usize, Option<usize>)
     {
         if start > end
         {
-            return None;
+            return (0, None);
         }
-
         let diff = *end - *start;
-
         match diff.exact()
         {
-            | true => Some(diff.floor() as usize),
-            | false => None,
+            | true => (diff.floor() as usize, Some(diff.floor() as usize)),
+            | false => (0, None),
         }
     }
 }
@@ -10,6 +10,8 @@ pub enum SearchArgs
 {
     Time(Duration),
     Depth(Depth),
+    /// A limit on the nodes searched, or the playouts for evaluators that play out positions.
+    Nodes(u64),
 }
 
 impl SearchArgs
@@ -20,7 +22,17 @@ impl SearchArgs
         match self
         {
             | Self::Depth(d) => *d,
-            | Self::Time(_) => Depth::MAX,
+            | Self::Time(_) | Self::Nodes(_) => Depth::MAX,
+        }
+    }
+
+    /// Determines the hard node limit.
+    pub fn nodes(&self) -> u64
+    {
+        match self
+        {
+            | Self::Nodes(n) => *n,
+            | Self::Time(_) | Self::Depth(_) => u64::MAX,
         }
     }
 
@@ -33,7 +45,7 @@ impl SearchArgs
         {
             let err = Error::new(
                 Kind::ParseError,
-                "Search options require a mode (time or depth) and a corresponding value.".into(),
+                "Search options require a mode (time, depth or nodes) and a corresponding value.".into(),
             );
             return Err(err.chain(base));
         }
@@ -96,6 +108,16 @@ impl SearchArgs
                 };
                 Ok(SearchArgs::Depth(depth))
             }
+            | "nodes" =>
+            {
+                let Ok(nodes) = args[1].parse::<u64>()
+                else
+                {
+                    let err = Error::for_parse::<u64>(args[1].to_owned());
+                    return Err(err.chain(base));
+                };
+                Ok(SearchArgs::Nodes(nodes))
+            }
             | _ => Err(base),
         }
     }
@@ -46,50 +46,33 @@ impl From<TTBound> for i32
 {
     fn from(value: TTBound) -> i32
     {
-        unsafe { std::mem::transmute::<TTBound, u8>(value) as i32 }
+        value as u8 as i32
     }
 }
 
-#[allow(unused)]
-#[repr(C)]
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
-/// Data associated with the most recent evaluation of a particular board state.
-pub struct TTEntry
-{
-    // This entry's main k-v.
-    pub key:   ZobristHash, // 16
-    pub mv:    MoveToken,   // 4
-    pub depth: Depth,       // 4
-    pub score: i32,         // 4
-    pub age:   TTAge,       // 2
-}
-
-impl From<TTEntryData> for TTEntry
+impl From<u8> for TTBound
 {
-    fn from(value: TTEntryData) -> Self
+    fn from(value: u8) -> TTBound
     {
-        unsafe { std::mem::transmute(value) }
+        match value & 0b11
+        {
+            | 1 => TTBound::Upper,
+            | 2 => TTBound::Lower,
+            | 3 => TTBound::Exact,
+            | _ => TTBound::None,
+        }
     }
 }
 
-impl TTEntry
-{
-    /// The size in bytes of an entry.
-    pub const SIZE: usize = std::mem::size_of::<Self>();
-
-    #[allow(clippy::assertions_on_constants)]
-    const __TTENTRY_SIZE_CORRECT: () = assert!(Self::SIZE == 32);
-}
-
-/// A type alias representing data that is generated from a TTEntry.
-pub type TTEntryData = [u64; 4];
-
-impl From<TTEntry> for TTEntryData
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// Data associated with the most recent evaluation of a particular board state.
+pub struct TTEntry
 {
-    fn from(value: TTEntry) -> Self
-    {
-        unsafe { std::mem::transmute(value) }
-    }
+    pub key:   ZobristHash,
+    pub mv:    MoveToken,
+    pub depth: Depth,
+    pub score: i32,
+    pub age:   TTAge,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
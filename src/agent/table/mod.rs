@@ -1,32 +1,43 @@
-use std::sync::{
-    atomic::{AtomicU8, Ordering},
-    Arc,
-};
-
-use dashmap::DashMap;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
 
 use crate::prelude::*;
 
 mod entry;
+mod persist;
 mod token;
 
-pub use entry::{TTAge, TTBound, TTEntry, TTEntryData, TTHit};
+pub use entry::{TTAge, TTBound, TTEntry, TTHit};
 pub use token::MoveToken;
 
+#[derive(Debug, Default)]
+/// A single entry in the table.
+///
+/// The key is stored XORed with the data, so that an entry torn by two threads writing at once reads back as a miss
+/// instead of another position's data.
+struct Slot
+{
+    key:  AtomicU64,
+    data: AtomicU64,
+}
+
+#[repr(align(64))]
+#[derive(Debug, Default)]
+/// A cache line of entries, which are always probed together.
+struct Bucket([Slot; TranspositionTable::BUCKET_SIZE]);
+
 #[derive(Debug)]
-/// A lockfree, concurrent implementation of a transposition table.
+/// A lockless, concurrent transposition table made of fixed-size buckets.
 pub struct TranspositionTable
 {
-    map: Arc<DashMap<u128, TTEntryData>>,
-    age: AtomicU8,
-    cap: usize,
+    buckets: Box<[Bucket]>,
+    age:     AtomicU8,
 }
 
-/// The sort of reference we get into the dashmap, but we want to hold onto it as little as possible.
-type TTRef<'a> = dashmap::mapref::one::Ref<'a, u128, TTEntryData>;
-
 impl TranspositionTable
 {
+    /// The number of entries in a bucket.
+    pub const BUCKET_SIZE: usize = 4;
+
     /// The upper bound on the table's age.
     const EXTENT_AGE: u8 = 0x3F;
 
@@ -68,7 +79,18 @@ impl TranspositionTable
         None
     }
 
-    /// Loads a variation from the table.
+    /// Forgets every entry, and starts counting searches from zero again.
+    pub fn clear(&self)
+    {
+        for slot in self.buckets.iter().flat_map(|bucket| bucket.0.iter())
+        {
+            slot.key.store(0, Ordering::Relaxed);
+            slot.data.store(0, Ordering::Relaxed);
+        }
+        self.age.store(0, Ordering::Relaxed);
+    }
+
+    /// Loads a variation from the table, stopping at the first move that is not legal in its position.
     pub fn get_principal_variation(&self, board: &Board, variation: &mut Variation)
     {
         variation.moves.clear();
@@ -81,6 +103,18 @@ impl TranspositionTable
         while let Some(hit) = self.load(zobrist)
         {
             let mv: Move = Option::<Move>::from(hit.mv).unwrap_or(Move::Pass);
+
+            let legal = match mv
+            {
+                | Move::Pass => board.generate_moves(false).is_empty(),
+                | _ => board.is_legal(&mv),
+            };
+
+            if !legal
+            {
+                break;
+            }
+
             variation.moves.push(ScoredMove { mv, score: hit.score });
 
             board.play_unchecked(&mv);
@@ -99,6 +133,24 @@ impl TranspositionTable
         }
     }
 
+    /// Estimates how full the table is with entries from the current search, in permille.
+    pub fn hashfull(&self) -> usize
+    {
+        const SAMPLE: usize = 1000 / TranspositionTable::BUCKET_SIZE;
+
+        let age = self.age.load(Ordering::Relaxed);
+        let sample = &self.buckets[..SAMPLE.min(self.buckets.len())];
+
+        let used = sample
+            .iter()
+            .flat_map(|bucket| bucket.0.iter())
+            .map(|slot| Self::unpack(0, slot.data.load(Ordering::Relaxed)))
+            .filter(|entry| entry.age.bound != TTBound::None && entry.age.age == age)
+            .count();
+
+        1000 * used / (sample.len() * Self::BUCKET_SIZE)
+    }
+
     /// Increments the age of the table.
     pub fn increment(&self)
     {
@@ -109,91 +161,159 @@ impl TranspositionTable
     /// Finds the hitinfo associated with this board state, if one exists.
     pub fn load(&self, key: ZobristHash) -> Option<TTHit>
     {
-        self.get(&key).map(|e| {
-            let entry: TTEntry = e.to_owned().into();
+        let folded = Self::fold(key);
 
-            TTHit {
-                key:   entry.key,
-                mv:    entry.mv,
+        self.bucket(folded).0.iter().find_map(|slot| {
+            let data = slot.data.load(Ordering::Relaxed);
+            let check = slot.key.load(Ordering::Relaxed) ^ data;
+
+            let entry = Self::unpack(key, data);
+            (check == folded && entry.age.bound != TTBound::None).then_some(TTHit {
+                key,
+                mv: entry.mv,
                 depth: entry.depth,
                 bound: entry.age.bound,
                 score: entry.score,
-            }
+            })
         })
     }
 
+    /// The memory in bytes used by the table, which is allocated up front and never grows.
+    pub fn memory(&self) -> usize
+    {
+        std::mem::size_of_val(&*self.buckets)
+    }
+
     /// Creates a new transposition table with the given memory constraints.
     pub fn new(bytes: usize) -> TranspositionTable
     {
-        // Get the number of entries that fit in our table.
-        let cap = bytes / TTEntry::SIZE;
-        log::trace!("Allocated a TranspositionTable with {} entries. ({} bytes)", cap, bytes);
+        // Get the number of buckets that fit in our table.
+        let count = (bytes / std::mem::size_of::<Bucket>()).max(1);
+        log::trace!("Allocated a TranspositionTable with {} buckets. ({} bytes)", count, bytes);
 
         TranspositionTable {
-            map: Arc::new(DashMap::with_capacity(cap)),
-            age: AtomicU8::new(0),
-            cap,
+            buckets: std::iter::repeat_with(Bucket::default).take(count).collect(),
+            age:     AtomicU8::new(0),
         }
     }
 
     /// Stores a new evaluation into the transposition table.
+    ///
+    /// An entry for the same position is only overwritten by one that is at least as deep, exact, or from a newer search.
+    /// Otherwise, the entry replaced is the shallowest one, where entries from older searches count as shallower.
     pub fn store(&self, entry: &TTEntry)
     {
         let mut entry = *entry;
-        let existing: Option<TTEntry> = self.get(&entry.key).map(|e| e.to_owned().into());
+        entry.age.age = self.age.load(Ordering::Relaxed);
+
+        self.place(Self::fold(entry.key), entry);
+    }
+}
 
-        match existing
+/// Private packing and indexing implementation for the table.
+impl TranspositionTable
+{
+    const OFFSET_SCORE: u32 = 0x10;
+    const OFFSET_DEPTH: u32 = 0x20;
+    const OFFSET_BOUND: u32 = 0x30;
+    const OFFSET_AGE: u32 = 0x32;
+
+    /// How many searches ago an entry was written.
+    fn age_distance(&self, age: u8) -> i32
+    {
+        let current = self.age.load(Ordering::Relaxed);
+        (Self::EXTENT_AGE & current.wrapping_sub(age)) as i32
+    }
+
+    /// Gets the bucket that a folded key lives in.
+    fn bucket(&self, folded: u64) -> &Bucket
+    {
+        let index = (folded as u128 * self.buckets.len() as u128) >> 64;
+        &self.buckets[index as usize]
+    }
+
+    /// Places an entry, keeping its age, into the bucket for its folded key.
+    fn place(&self, folded: u64, mut entry: TTEntry)
+    {
+        let bucket = self.bucket(folded);
+
+        let mut victim = &bucket.0[0];
+        let mut victim_worth = i32::MAX;
+
+        for slot in bucket.0.iter()
         {
-            | Some(prev) =>
+            let data = slot.data.load(Ordering::Relaxed);
+            let prev = Self::unpack(entry.key, data);
+
+            if prev.age.bound == TTBound::None
+            {
+                victim = slot;
+                break;
+            }
+
+            if slot.key.load(Ordering::Relaxed) ^ data == folded
             {
-                entry.mv = if entry.mv.is_some() { entry.mv } else { prev.mv };
+                if !entry.mv.is_some()
+                {
+                    entry.mv = prev.mv;
+                }
+
+                let replace = entry.age.bound == TTBound::Exact && prev.age.bound != TTBound::Exact
+                    || prev.age.age != entry.age.age
+                    || prev.depth <= entry.depth;
 
-                if entry.key != prev.key
-                    || entry.age.bound == TTBound::Exact && prev.age.bound != TTBound::Exact
-                    || self.should_overwrite(&prev, &entry)
+                if !replace
                 {
-                    let data: TTEntryData = entry.into();
-                    self.map.insert(entry.key, data);
+                    return;
                 }
+
+                victim = slot;
+                break;
             }
-            | None =>
+
+            let worth = i32::from(prev.depth) - 8 * i32::from(Depth::PLY) * self.age_distance(prev.age.age);
+            if worth < victim_worth
             {
-                let data: TTEntryData = entry.into();
-                self.put(&entry.key, data);
+                victim = slot;
+                victim_worth = worth;
             }
-        };
-    }
-}
+        }
 
-/// Private mapping implementation for the table.
-impl TranspositionTable
-{
-    /// Returns the key modulo the maximum number of entries, avoiding reallocation.
-    fn capacity_hash(&self, key: &ZobristHash) -> u128
-    {
-        key % (self.cap as u128)
+        let data = Self::pack(&entry);
+        victim.data.store(data, Ordering::Relaxed);
+        victim.key.store(folded ^ data, Ordering::Relaxed);
     }
 
-    /// Gets an entry from the table, ensuring we don't overdo the capacity.
-    fn get(&self, key: &ZobristHash) -> Option<TTRef<'_>>
+    /// Folds the full hash, including the Pillbug and player bits, into the 64 bits that are stored.
+    fn fold(key: ZobristHash) -> u64
     {
-        let meta_key = self.capacity_hash(key);
-        self.map.get(&meta_key)
+        (key as u64) ^ ((key >> 64) as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
     }
 
-    /// Puts an entry into the table, ensuring we don't overdo the capacity.
-    fn put(&self, key: &ZobristHash, data: TTEntryData)
+    /// Packs everything but the key into 64 bits.
+    fn pack(entry: &TTEntry) -> u64
     {
-        let meta_key = self.capacity_hash(key);
-        self.map.insert(meta_key, data);
+        let mv = u16::from(entry.mv) as u64;
+        let score = (entry.score as i16 as u16 as u64) << Self::OFFSET_SCORE;
+        let depth = (i32::from(entry.depth) as i16 as u16 as u64) << Self::OFFSET_DEPTH;
+        let bound = (entry.age.bound as u64) << Self::OFFSET_BOUND;
+        let age = ((entry.age.age & Self::EXTENT_AGE) as u64) << Self::OFFSET_AGE;
+
+        mv | score | depth | bound | age
     }
 
-    /// Whether or not to overwrite an entry based on age priority.
-    fn should_overwrite(&self, prev: &TTEntry, next: &TTEntry) -> bool
+    /// Unpacks an entry for the given key.
+    fn unpack(key: ZobristHash, data: u64) -> TTEntry
     {
-        prev.age.age != self.age.load(Ordering::SeqCst) || prev.depth <= next.depth
+        TTEntry {
+            key,
+            mv: MoveToken::from(data as u16),
+            score: (data >> Self::OFFSET_SCORE) as u16 as i16 as i32,
+            depth: Depth::raw((data >> Self::OFFSET_DEPTH) as u16 as i16 as i32),
+            age: TTAge {
+                age:   (data >> Self::OFFSET_AGE) as u8 & Self::EXTENT_AGE,
+                bound: TTBound::from((data >> Self::OFFSET_BOUND) as u8),
+            },
+        }
     }
 }
-
-/// Because the LSP isn't giving me confidence that this is true!
-unsafe impl std::marker::Sync for TranspositionTable {}
@@ -0,0 +1,127 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+    sync::atomic::Ordering,
+};
+
+use super::TranspositionTable;
+use crate::prelude::*;
+
+/// Saving and restoring the table, so that a long analysis can be resumed later.
+///
+/// File layout, all little-endian:
+///     magic (4 bytes), format version (u32), zobrist fingerprint (u64), table age (u8), entry count (u64)
+///     then one (folded key: u64, data: u64) pair per non-empty entry
+impl TranspositionTable
+{
+    const MAGIC: [u8; 4] = *b"HVTT";
+    const VERSION: u32 = 1;
+
+    /// Restores the entries saved in a file, on top of what is already here.
+    ///
+    /// The table does not need to be the same size as the one that was saved.
+    pub fn restore(&self, path: &Path) -> Result<usize>
+    {
+        let file = File::open(path).map_err(|err| Self::file_error(err, "read", path))?;
+        let mut reader = BufReader::new(file);
+        let mut read = |bytes: &mut [u8]| reader.read_exact(bytes).map_err(|err| Self::file_error(err, "read", path));
+
+        let mut magic = [0u8; 4];
+        read(&mut magic)?;
+        if magic != Self::MAGIC
+        {
+            return Err(Error::new(Kind::ParseError, format!("'{}' is not a saved table.", path.display())));
+        }
+
+        let mut word = [0u8; 4];
+        read(&mut word)?;
+        let version = u32::from_le_bytes(word);
+        if version != Self::VERSION
+        {
+            return Err(Error::mismatch(Self::VERSION, version)
+                .chain(Error::new(Kind::MismatchError, "The table was saved in a different format.".into())));
+        }
+
+        let mut long = [0u8; 8];
+        read(&mut long)?;
+        let fingerprint = u64::from_le_bytes(long);
+        if fingerprint != Board::zobrist_fingerprint()
+        {
+            return Err(Error::new(
+                Kind::MismatchError,
+                "The table was saved by a build with different zobrist keys.".into(),
+            ));
+        }
+
+        let mut age = [0u8; 1];
+        read(&mut age)?;
+        self.age.store(age[0] & Self::EXTENT_AGE, Ordering::Relaxed);
+
+        read(&mut long)?;
+        let count = u64::from_le_bytes(long) as usize;
+
+        let mut pair = [0u8; 16];
+        for _ in 0..count
+        {
+            read(&mut pair)?;
+            let folded = u64::from_le_bytes(pair[..8].try_into().unwrap());
+            let data = u64::from_le_bytes(pair[8..].try_into().unwrap());
+
+            self.place(folded, Self::unpack(0, data));
+        }
+
+        log::info!("Restored {} transpositions from '{}'.", count, path.display());
+        Ok(count)
+    }
+
+    /// Saves every entry in the table to a file, returning how many were written.
+    pub fn save(&self, path: &Path) -> Result<usize>
+    {
+        let entries = self
+            .buckets
+            .iter()
+            .flat_map(|bucket| bucket.0.iter())
+            .map(|slot| {
+                let data = slot.data.load(Ordering::Relaxed);
+                (slot.key.load(Ordering::Relaxed) ^ data, data)
+            })
+            .filter(|(_, data)| Self::unpack(0, *data).age.bound != TTBound::None)
+            .collect::<Vec<(u64, u64)>>();
+
+        let file = File::create(path).map_err(|err| Self::file_error(err, "write", path))?;
+        let mut writer = BufWriter::new(file);
+
+        let mut header = Vec::with_capacity(25);
+        header.extend(Self::MAGIC);
+        header.extend(Self::VERSION.to_le_bytes());
+        header.extend(Board::zobrist_fingerprint().to_le_bytes());
+        header.push(self.age.load(Ordering::Relaxed));
+        header.extend((entries.len() as u64).to_le_bytes());
+
+        let mut write = |bytes: &[u8]| writer.write_all(bytes).map_err(|err| Self::file_error(err, "write", path));
+
+        write(&header)?;
+        for (folded, data) in entries.iter()
+        {
+            write(&folded.to_le_bytes())?;
+            write(&data.to_le_bytes())?;
+        }
+        writer.flush().map_err(|err| Self::file_error(err, "write", path))?;
+
+        log::info!("Saved {} transpositions to '{}'.", entries.len(), path.display());
+        Ok(entries.len())
+    }
+}
+
+impl TranspositionTable
+{
+    /// Wraps an IO error, which would otherwise be fatal, as a recoverable one.
+    fn file_error(err: std::io::Error, action: &str, path: &Path) -> Error
+    {
+        Error::from(err).chain(Error::new(
+            Kind::InvalidOption,
+            format!("Could not {} the table at '{}'.", action, path.display()),
+        ))
+    }
+}
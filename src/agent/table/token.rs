@@ -1,18 +1,15 @@
 use crate::prelude::*;
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
-/// A wrapped type for a move.
+/// A wrapped type for a move, compact enough to share a transposition table entry with its score.
 ///
 /// Bits:
 ///     00 - 04: piece
-///     05 - 05: piece.is_some()
-///     06 - 0A: nextto.piece
-///     0B - 1A: nextto.direction
-///     1B - 1B: nextto.direction.is_some()
-///     1C - 1C: nextto.is_some()
-///     1D - 1E: move::enum_type [Pass, Place, Move]
-///     1F - 1F: move.is_some()
-pub struct MoveToken(u32);
+///     05 - 09: nextto.piece
+///     0A - 0C: nextto.direction, as an index into [Direction::all()]; or on top; or no nextto at all
+///     0D - 0E: move::enum_type [None, Pass, Move, Place]
+///     0F - 0F: unused
+pub struct MoveToken(u16);
 
 impl From<Move> for MoveToken
 {
@@ -20,39 +17,9 @@ impl From<Move> for MoveToken
     {
         match value
         {
-            | Move::Move(piece, nextto) =>
-            {
-                let set = 1u32 << Self::OFFSET_MOVE_OPT;
-                let p__ = (piece.index() as u32) << Self::OFFSET_PIECE;
-                let po_ = 1u32 << Self::OFFSET_PIECE_OPT;
-                let np_ = (nextto.piece.index() as u32) << Self::OFFSET_NEXTTO_PIECE;
-                let nd_ = (nextto.direction.unwrap_or(Direction::East) as u32) << Self::OFFSET_NEXTTO_DIRECTION;
-                let ndo = (nextto.direction.is_some() as u32) << Self::OFFSET_NEXTTO_DIRECTION_OPT;
-                let no_ = 1u32 << Self::OFFSET_NEXTTO_OPT;
-                let et_ = Self::TYPE_MOVE << Self::OFFSET_ENUM_TYPE;
-
-                MoveToken(set | p__ | po_ | np_ | nd_ | ndo | no_ | et_)
-            }
-            | Move::Place(piece, nextto) =>
-            {
-                let set = 1u32 << Self::OFFSET_MOVE_OPT;
-                let p__ = (piece.index() as u32) << Self::OFFSET_PIECE;
-                let po_ = 1u32 << Self::OFFSET_PIECE_OPT;
-                let np_ = (nextto.map(|n| n.piece.index()).unwrap_or(0) as u32) << Self::OFFSET_NEXTTO_PIECE;
-                let nd_ = (nextto.map(|n| n.direction.unwrap_or(Direction::East)).unwrap_or(Direction::East) as u32) << Self::OFFSET_NEXTTO_DIRECTION;
-                let ndo = (nextto.map(|n| n.direction).unwrap_or(None).is_some() as u32) << Self::OFFSET_NEXTTO_DIRECTION_OPT;
-                let no_ = (nextto.is_some() as u32) << Self::OFFSET_NEXTTO_OPT;
-                let et_ = Self::TYPE_PLACE << Self::OFFSET_ENUM_TYPE;
-
-                MoveToken(set | p__ | po_ | np_ | nd_ | ndo | no_ | et_)
-            }
-            | Move::Pass =>
-            {
-                let set = 1u32 << Self::OFFSET_MOVE_OPT;
-                let et_ = Self::TYPE_PASS << Self::OFFSET_ENUM_TYPE;
-
-                MoveToken(set | et_)
-            }
+            | Move::Move(piece, nextto) => MoveToken::pack(Self::TYPE_MOVE, piece.index(), Some(nextto)),
+            | Move::Place(piece, nextto) => MoveToken::pack(Self::TYPE_PLACE, piece.index(), nextto),
+            | Move::Pass => MoveToken(Self::TYPE_PASS << Self::OFFSET_ENUM_TYPE),
         }
     }
 }
@@ -61,119 +28,108 @@ impl From<MoveToken> for Option<Move>
 {
     fn from(value: MoveToken) -> Self
     {
-        match value.is_some()
+        match value.enum_type()
         {
-            | true => Some(match value.enum_type()
-            {
-                | MoveToken::TYPE_PASS => Move::Pass,
-                | MoveToken::TYPE_MOVE =>
-                {
-                    let piece = value.piece().unwrap();
-                    let nextto = value.nextto().unwrap();
-                    Move::Move(piece, nextto)
-                }
-                | MoveToken::TYPE_PLACE =>
-                {
-                    let piece = value.piece().unwrap();
-                    let nextto = value.nextto();
-                    Move::Place(piece, nextto)
-                }
-                | _ => unreachable!(),
-            }),
-            | false => None,
+            | MoveToken::TYPE_PASS => Some(Move::Pass),
+            | MoveToken::TYPE_MOVE => Some(Move::Move(value.piece(), value.nextto()?)),
+            | MoveToken::TYPE_PLACE => Some(Move::Place(value.piece(), value.nextto())),
+            | _ => None,
         }
     }
 }
 
-impl MoveToken
+impl From<u16> for MoveToken
 {
-    const OFFSET_PIECE: u32 = 0x0;
-    const OFFSET_PIECE_OPT: u32 = 0x5;
-    const OFFSET_NEXTTO_PIECE: u32 = 0x6;
-    const OFFSET_NEXTTO_DIRECTION: u32 = 0xB;
-    const OFFSET_NEXTTO_DIRECTION_OPT: u32 = 0x1B;
-    const OFFSET_NEXTTO_OPT: u32 = 0x1C;
-    const OFFSET_ENUM_TYPE: u32 = 0x1D;
-    const OFFSET_MOVE_OPT: u32 = 0x1F;
-
-    const EXTENT_DIRECTION: u32 = u16::MAX as u32;
-    const EXTENT_ENUM_TYPE: u32 = 0b11;
-    const EXTENT_OPTION: u32 = 0b1;
-    const EXTENT_PIECE: u32 = 0b11111;
-
-    const TYPE_PASS: u32 = 0x0;
-    const TYPE_MOVE: u32 = 0x1;
-    const TYPE_PLACE: u32 = 0x2;
-
-    /// Extracts the direction in a NextTo provided one exists.
-    fn direction(&self) -> Option<Direction>
+    fn from(value: u16) -> Self
     {
-        if self.is_set(Self::OFFSET_NEXTTO_DIRECTION_OPT)
-        {
-            let val = ((self.0 >> Self::OFFSET_NEXTTO_DIRECTION) & Self::EXTENT_DIRECTION) as Hex;
-            let direction = unsafe { std::mem::transmute(val) };
-            Some(direction)
-        }
-        else
-        {
-            None
-        }
+        MoveToken(value)
     }
+}
 
-    /// Extracts the type.
-    fn enum_type(&self) -> u32
+impl From<MoveToken> for u16
+{
+    fn from(value: MoveToken) -> Self
     {
-        self.0 >> Self::OFFSET_ENUM_TYPE & Self::EXTENT_ENUM_TYPE
+        value.0
     }
+}
 
-    /// Ensures the given option is set.
-    fn is_set(&self, offset: u32) -> bool
-    {
-        ((self.0 >> offset) & Self::EXTENT_OPTION) == 1
-    }
+impl MoveToken
+{
+    const OFFSET_PIECE: u16 = 0x0;
+    const OFFSET_NEXTTO_PIECE: u16 = 0x5;
+    const OFFSET_NEXTTO_DIRECTION: u16 = 0xA;
+    const OFFSET_ENUM_TYPE: u16 = 0xD;
+
+    const EXTENT_DIRECTION: u16 = 0b111;
+    const EXTENT_ENUM_TYPE: u16 = 0b11;
+    const EXTENT_PIECE: u16 = 0b11111;
+
+    const DIRECTION_ON_TOP: u16 = 6;
+    const DIRECTION_NO_NEXTTO: u16 = 7;
+
+    const TYPE_NONE: u16 = 0x0;
+    const TYPE_PASS: u16 = 0x1;
+    const TYPE_MOVE: u16 = 0x2;
+    const TYPE_PLACE: u16 = 0x3;
 
     /// Whether or not this move is really a move.
     pub fn is_some(&self) -> bool
     {
-        self.is_set(Self::OFFSET_MOVE_OPT)
+        self.enum_type() != Self::TYPE_NONE
     }
+}
 
-    /// Extracts the reference hex.
+impl MoveToken
+{
+    /// Extracts the type.
+    fn enum_type(&self) -> u16
+    {
+        self.0 >> Self::OFFSET_ENUM_TYPE & Self::EXTENT_ENUM_TYPE
+    }
+
+    /// Extracts the reference piece and direction, if there is one.
     fn nextto(&self) -> Option<NextTo>
     {
-        if self.is_set(Self::OFFSET_NEXTTO_OPT)
+        let piece = ((self.0 >> Self::OFFSET_NEXTTO_PIECE) & Self::EXTENT_PIECE) as u8;
+
+        let direction = match (self.0 >> Self::OFFSET_NEXTTO_DIRECTION) & Self::EXTENT_DIRECTION
         {
-            let piece: Piece = (((self.0 >> Self::OFFSET_NEXTTO_PIECE) & Self::EXTENT_PIECE) as u8).into();
-
-            let direction = if self.is_set(Self::OFFSET_NEXTTO_DIRECTION_OPT)
-            {
-                let dir: Direction = self.direction().unwrap();
-                Some(dir)
-            }
-            else
-            {
-                None
-            };
-
-            Some(NextTo { piece, direction })
-        }
-        else
+            | Self::DIRECTION_NO_NEXTTO => return None,
+            | Self::DIRECTION_ON_TOP => None,
+            | index => Some(Direction::all()[index as usize]),
+        };
+
+        Some(NextTo {
+            piece: piece.into(),
+            direction,
+        })
+    }
+
+    /// Packs a placement or movement.
+    fn pack(enum_type: u16, piece: u16, nextto: Option<NextTo>) -> MoveToken
+    {
+        let (reference, direction) = match nextto
         {
-            None
-        }
+            | Some(NextTo { piece, direction: None }) => (piece.index(), Self::DIRECTION_ON_TOP),
+            | Some(NextTo {
+                piece,
+                direction: Some(direction),
+            }) => (piece.index(), Direction::all().iter().position(|d| *d == direction).unwrap() as u16),
+            | None => (0, Self::DIRECTION_NO_NEXTTO),
+        };
+
+        let p_ = piece << Self::OFFSET_PIECE;
+        let np = reference << Self::OFFSET_NEXTTO_PIECE;
+        let nd = direction << Self::OFFSET_NEXTTO_DIRECTION;
+        let et = enum_type << Self::OFFSET_ENUM_TYPE;
+
+        MoveToken(p_ | np | nd | et)
     }
 
     /// Extracts the piece.
-    fn piece(&self) -> Option<Piece>
+    fn piece(&self) -> Piece
     {
-        if self.is_set(Self::OFFSET_PIECE_OPT)
-        {
-            let index: u8 = ((self.0 >> Self::OFFSET_PIECE) & Self::EXTENT_PIECE) as u8;
-            Some(index.into())
-        }
-        else
-        {
-            None
-        }
+        (((self.0 >> Self::OFFSET_PIECE) & Self::EXTENT_PIECE) as u8).into()
     }
 }
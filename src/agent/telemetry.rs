@@ -0,0 +1,68 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::prelude::*;
+
+#[derive(Debug, Default)]
+/// An optional sink for per-search records, written as one JSON object per line so that they can be aggregated over
+/// many games.
+pub struct Telemetry
+{
+    path:   Option<PathBuf>,
+    writer: Option<BufWriter<File>>,
+}
+
+impl Telemetry
+{
+    /// Opens a file to append records to, or discards every record if there is no file.
+    pub fn open(path: Option<&Path>) -> Result<Telemetry>
+    {
+        let Some(path) = path
+        else
+        {
+            return Ok(Telemetry::default());
+        };
+
+        let file = OpenOptions::new().create(true).append(true).open(path).map_err(|err| {
+            let base = Error::new(Kind::InvalidOption, format!("Could not open '{}' for telemetry.", path.display()));
+            Error::from(err).chain(base)
+        })?;
+
+        Ok(Telemetry {
+            path:   Some(path.to_path_buf()),
+            writer: Some(BufWriter::new(file)),
+        })
+    }
+
+    /// Whether records are being written anywhere.
+    pub fn is_enabled(&self) -> bool
+    {
+        self.writer.is_some()
+    }
+
+    /// The file records are appended to, if any.
+    pub fn path(&self) -> Option<&Path>
+    {
+        self.path.as_deref()
+    }
+
+    /// Appends a record as a single line, flushing it so that a record is never left half-written.
+    pub fn record<T: Serialize>(&mut self, record: &T) -> Result<()>
+    {
+        let Some(writer) = self.writer.as_mut()
+        else
+        {
+            return Ok(());
+        };
+
+        let line = serde_json::to_string(record).map_err(|err| Error::new(Kind::InternalError, err.to_string()))?;
+        writeln!(writer, "{}", line)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
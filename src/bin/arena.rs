@@ -0,0 +1,123 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::PathBuf,
+};
+
+use clap::Parser;
+use hivemind::prelude::*;
+
+#[derive(Clone, Parser, Debug)]
+#[command(version, about = "Plays a match between two sets of evaluation weights or skill levels.", long_about = None)]
+struct ArenaOptions
+{
+    #[arg(long, value_name = "FILE")]
+    /// evaluation weights for the first side, from a JSON or TOML file; defaults to the built-in weights
+    first: Option<PathBuf>,
+
+    #[arg(long, value_name = "FILE")]
+    /// evaluation weights for the second side, from a JSON or TOML file; defaults to the built-in weights
+    second: Option<PathBuf>,
+
+    #[arg(long, default_value_t = evaluators::Skill::MAX)]
+    /// skill level of the first side, from 0 up to full strength at 20
+    first_skill: u8,
+
+    #[arg(long, default_value_t = evaluators::Skill::MAX)]
+    /// skill level of the second side, from 0 up to full strength at 20
+    second_skill: u8,
+
+    #[arg(short, long, default_value_t = 10)]
+    /// number of games to play, swapping colours after each one
+    games: usize,
+
+    #[arg(short, long, default_value_t = 3)]
+    /// depth to search each move to
+    depth: i32,
+
+    #[arg(long)]
+    /// game to start every game from, as a GameString; defaults to a new base game
+    start: Option<String>,
+
+    #[arg(long, default_value_t = 0.1)]
+    /// maximum memory in GB for each side's transpositions and cache
+    memory: f64,
+
+    #[arg(short, long, default_value_t = 1)]
+    /// number of search threads for each side
+    num_threads: usize,
+
+    #[arg(short, long, value_name = "FILE")]
+    /// file to append each finished game to, as a result followed by a GameString
+    output: Option<PathBuf>,
+}
+
+impl ArenaOptions
+{
+    /// Builds the engine options for one side of the match.
+    fn side(&self, eval_params: &Option<PathBuf>, skill_level: u8) -> Result<UhpOptions>
+    {
+        let mut options = UhpOptions::parse_from(["hivemind"]);
+        options.cache_memory = self.memory;
+        options.table_memory = self.memory;
+        options.num_threads = self.num_threads;
+        options.skill_level = skill_level;
+        options.eval_params = eval_params.clone();
+        options.load_weights()?;
+        Ok(options)
+    }
+}
+
+fn main() -> Result<()>
+{
+    let arena = ArenaOptions::parse();
+
+    let start: Board = match &arena.start
+    {
+        | Some(gamestring) => gamestring.parse::<GameString>()?.into(),
+        | None => Board::default(),
+    };
+
+    let mut first = evaluators::Strongest::new(arena.side(&arena.first, arena.first_skill)?);
+    let mut second = evaluators::Strongest::new(arena.side(&arena.second, arena.second_skill)?);
+
+    let mut output = match &arena.output
+    {
+        | Some(path) => Some(OpenOptions::new().create(true).append(true).open(path)?),
+        | None => None,
+    };
+
+    let args = SearchArgs::Depth(Depth::new(arena.depth));
+    let report = arena::play_match(&mut first, &mut second, &start, arena.games, args, |game, board, report| {
+        if let Some(file) = output.as_mut()
+        {
+            if let Err(err) = writeln!(file, "{}", GameRecord::new(board.clone()))
+            {
+                eprintln!("could not write game {}: {}", game + 1, err);
+            }
+        }
+
+        println!(
+            "game {}: {} after {} turns (+{} ={} -{})",
+            game + 1,
+            board.state(),
+            board.turn(),
+            report.wins,
+            report.draws,
+            report.losses
+        );
+    })?;
+
+    let elo = report.elo().map(|elo| format!("{:+.0}", elo)).unwrap_or("n/a".into());
+    println!(
+        "first scored {:.1}% over {} games (+{} ={} -{}), elo difference {}",
+        100.0 * report.score(),
+        report.games(),
+        report.wins,
+        report.draws,
+        report.losses,
+        elo
+    );
+
+    Ok(())
+}
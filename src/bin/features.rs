@@ -0,0 +1,44 @@
+use std::{
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+
+use clap::Parser;
+use hivemind::prelude::*;
+
+#[derive(Clone, Parser, Debug)]
+#[command(version, about = "Writes the linear evaluator's features for every position in a corpus of games, as CSV.", long_about = None)]
+struct FeatureOptions
+{
+    /// games to read, one per line as a result (1-0, 0-1 or 1/2-1/2) followed by a GameString
+    corpus: PathBuf,
+
+    #[arg(short, long, default_value_t = 8)]
+    /// turn to start taking positions from in each game
+    skip: u8,
+}
+
+fn main() -> Result<()>
+{
+    let options = FeatureOptions::parse();
+    let games = read_corpus(&options.corpus)?;
+
+    let mut out = BufWriter::new(std::io::stdout().lock());
+    writeln!(out, "{},result", evaluators::Features::NAMES.join(","))?;
+
+    for game in games
+    {
+        for board in game.positions(options.skip)
+        {
+            // Both the features and the result are from the perspective of the player to move.
+            let result = if board.to_move() == Player::White { game.result } else { 1.0 - game.result };
+            let evaluators::Features(features) = evaluators::Features::extract(&board);
+
+            let row = features.iter().map(|f| f.to_string()).collect::<Vec<_>>().join(",");
+            writeln!(out, "{},{}", row, result)?;
+        }
+    }
+
+    out.flush()?;
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use hivemind::prelude::*;
+
+#[derive(Clone, Parser, Debug)]
+#[command(version, about = "Fits the evaluation weights to the results of a corpus of games.", long_about = None)]
+struct TuneOptions
+{
+    /// games to fit, one per line as a result (1-0, 0-1 or 1/2-1/2) followed by a GameString
+    corpus: PathBuf,
+
+    #[arg(long, value_name = "FILE")]
+    /// weights to start from, from a JSON or TOML file; defaults to the built-in weights
+    start: Option<PathBuf>,
+
+    #[arg(short, long, value_name = "FILE")]
+    /// file to write the tuned weights to, as TOML if it ends in .toml and JSON otherwise
+    output: Option<PathBuf>,
+
+    #[arg(short, long, default_value_t = 100)]
+    /// maximum number of rounds of local search
+    rounds: usize,
+
+    #[arg(short, long, default_value_t = 8)]
+    /// turn to start taking positions from in each game
+    skip: u8,
+
+    #[arg(short, long, default_value_t = 4)]
+    /// number of threads to evaluate positions with
+    num_threads: usize,
+}
+
+fn main() -> Result<()>
+{
+    let options = TuneOptions::parse();
+
+    let start = match &options.start
+    {
+        | Some(path) => evaluators::EvalParams::load(path)?,
+        | None => evaluators::EvalParams::default(),
+    };
+
+    let games = read_corpus(&options.corpus)?;
+    let mut tuner = evaluators::Tuner::new(&start, &games, options.skip, options.num_threads);
+    if tuner.is_empty()
+    {
+        return Err(Error::new(Kind::InvalidOption, "The corpus has no positions to fit.".into()));
+    }
+
+    let scale = tuner.fit_scale(&start);
+    eprintln!(
+        "fitting {} positions from {} games, scale {:.5}, starting loss {:.6}",
+        tuner.len(),
+        games.len(),
+        scale,
+        tuner.loss(&start)
+    );
+
+    let tuned = tuner.tune(&start, options.rounds, |round, loss, _| {
+        eprintln!("round {}: loss {:.6}", round + 1, loss);
+    });
+
+    if let Some(path) = &options.output
+    {
+        tuned.save(path)?;
+        eprintln!("saved weights to {}", path.display());
+    }
+
+    println!("{}", serde_json::to_string_pretty(&tuned).map_err(|err| Error::new(Kind::InternalError, err.to_string()))?);
+    Ok(())
+}
@@ -39,94 +39,194 @@ impl Board
         moves
     }
 
-    /// Only generates tactical moves for quiescence search.
-    pub fn generate_tactical_moves(&self) -> Vec<Move>
+    /// Generates true moves for the player to move, not including throws or placements.
+    pub fn generate_movements(&self) -> Vec<Move>
+    {
+        let mut moves: Vec<Move> = Vec::new();
+        self.generate_moves_into(&mut moves);
+        moves
+    }
+
+    /// Generates placements for the player to move.
+    pub fn generate_placements(&self, standard_position: bool) -> Vec<Move>
+    {
+        let mut moves: Vec<Move> = Vec::new();
+        self.generate_placements_into(standard_position, &mut moves);
+        moves
+    }
+
+    /// Generates Pillbug throws (including Mosquito-as-Pillbug throws) for the player to move.
+    pub fn generate_throws(&self) -> Vec<Move>
     {
-        // Don't waste time here in the opening.
-        if self.turn() < 8
+        let mut moves: Vec<Move> = Vec::new();
+        self.generate_throws_into(&mut moves);
+        moves
+    }
+
+    /// Counts the distinct hexes a piece on the board could move to on its owner's turn, whoever is to move now. Throws
+    /// by a Pillbug are not counted.
+    pub fn mobility(&self, piece: &Piece) -> usize
+    {
+        if self.queen(piece.player).is_none() || !self.is_free(piece)
         {
-            return Vec::new();
+            return 0;
         }
 
-        let mut moves: Vec<Move> = Vec::new();
-        let past = self.history.get_past();
+        let mut destinations = Vec::new();
+        self.generate_destinations_for(piece, &mut destinations);
 
-        // Check our last placement to see what its extensions are.
-        // This is because placing a piece is a loss of pinning tempo on the board,
-        // and should yield power elsewhere.
-        'attack: {
-            if let Move::Place(piece, _) = past[past.len() - 2].mv
-            {
-                let entry = past[past.len() - 2];
-                let destination = entry.patch.unwrap().to;
+        destinations.sort_unstable();
+        destinations.dedup();
+        destinations.len()
+    }
+
+    /// Returns the distinct hexes a piece on the board could move to on its owner's turn, whoever is to move now. Throws
+    /// by a Pillbug are not included.
+    pub fn destinations(&self, piece: &Piece) -> HashSet<Hex>
+    {
+        if self.queen(piece.player).is_none() || !self.is_free(piece)
+        {
+            return HashSet::new();
+        }
 
-                // Check if this is a direct drop. A direct drop implies we are covering
-                // the queen with a piece of our own, so we can't just check the tops of
-                // the neighbouring stacks.
+        let mut destinations = Vec::new();
+        self.generate_destinations_for(piece, &mut destinations);
+        destinations.into_iter().collect()
+    }
 
-                if let Some(enemy_queen_location) = self.queen(self.to_move().flip())
-                {
-                    let mut direct_drop = false;
-                    for direction in Direction::all()
-                    {
-                        let reference = destination + direction;
-                        if reference == enemy_queen_location
-                        {
-                            direct_drop = true;
-                            break;
-                        }
-                    }
-
-                    if direct_drop
-                    {
-                        break 'attack;
-                    }
-                }
-
-                // Otherwise, this is too quiet, so we should check extensions.
-
-                self.generate_moves_for(&piece, &mut moves);
-                return moves;
-            }
+    /// Counts the throws a Pillbug, or a Mosquito next to one, could make on its owner's turn, whoever is to move now.
+    pub fn throw_options(&self, thrower: &Piece) -> usize
+    {
+        let Some(intermediate) = self.location(thrower)
+        else
+        {
+            return 0;
+        };
+
+        let can_throw = match thrower.kind
+        {
+            | Bug::Pillbug => true,
+            | Bug::Mosquito => self.pieces_neighbouring(intermediate).iter().any(|piece| piece.kind == Bug::Pillbug),
+            | _ => false,
+        };
+
+        if !can_throw || self.queen(thrower.player).is_none() || self.stunned == Some(intermediate) || self.stacked(thrower)
+        {
+            return 0;
         }
 
-        // If we didn't just make a quiet move, but the opposing player did, generate
-        // a full subtree to allow the opposing player the opportunity to find extensions.
-        'defense: {
-            if let Move::Place(..) = past[past.len() - 1].mv
+        let neighbours = hex::neighbours(intermediate);
+        itertools::iproduct!(neighbours.iter(), neighbours.iter())
+            .filter(|(from, _)| self.immune != Some(**from))
+            .filter(|(from, _)| self.top(**from).is_some_and(|piece| self.ensure_one_hive_satisfied(&piece)))
+            .filter(|(from, to)| self.check_throw_via_satisfied(**from, *thrower, **to))
+            .count()
+    }
+
+    /// Generates the tactical moves for the player to move, which are the ones that change how surrounded a queen is.
+    ///
+    /// See [`Board::is_tactical`] for which moves count. Rather than generating every move and filtering them, only the
+    /// bugs that could make a tactical move are looked at: those on or next to a queen, those close enough to a liberty
+    /// of the enemy queen to reach it, and Pillbugs (or Mosquitos) close enough to a queen to throw next to it.
+    pub fn generate_tactical_moves(&self) -> Vec<Move>
+    {
+        let to_move = self.to_move();
+        let Some(friendly) = self.queen(to_move)
+        else
+        {
+            // Can't move or throw bugs until the queen is in the hive.
+            return Vec::new();
+        };
+        let enemy = self.queen(to_move.flip());
+
+        let queens = [Some(friendly), enemy].into_iter().flatten().collect::<Vec<_>>();
+        let liberties = enemy
+            .map(|queen| hex::neighbours(queen).into_iter().filter(|hex| !self.occupied(*hex)).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        // Bugs on or next to a queen may vacate a liberty or climb on or off, wherever they end up.
+        let around_queens = Self::within(&queens, 1);
+        // Other bugs need to get onto a liberty, which bugs that walk a set number of steps can only do from nearby.
+        // Climbers can also get onto a liberty that is already taken, as long as they are a step away from it.
+        let one_step = Self::within(&liberties, 1);
+        let three_steps = Self::within(&liberties, 3);
+        let climbs = Self::within(&enemy.into_iter().collect::<Vec<_>>(), 2);
+
+        let mut movements = Vec::new();
+        for (piece, from) in self.free_pieces(to_move)
+        {
+            let candidate = around_queens.contains(from)
+                || match piece.kind
+                {
+                    | Bug::Queen | Bug::Pillbug => one_step.contains(from),
+                    | Bug::Spider | Bug::Ladybug => three_steps.contains(from),
+                    | Bug::Ant | Bug::Grasshopper => !liberties.is_empty(),
+                    | Bug::Beetle => climbs.contains(from),
+                    | Bug::Mosquito => climbs.contains(from) || !liberties.is_empty(),
+                };
+
+            if candidate
             {
-                let entry = past[past.len() - 1];
-                let destination = entry.patch.unwrap().to;
+                self.generate_moves_for(&piece, &mut movements);
+            }
+        }
 
-                // Once again, check if this is a direct drop.
+        // A throw moves a bug between two neighbours of the thrower, so it can only touch a liberty from two hexes away.
+        let two_steps = Self::within(&queens, 2);
+        let throws = [Bug::Pillbug, Bug::Mosquito]
+            .into_iter()
+            .filter_map(|kind| self.location(&Piece { player: to_move, kind, num: 1 }))
+            .any(|hex| two_steps.contains(hex));
+        if throws
+        {
+            self.generate_throws_into(&mut movements);
+        }
 
-                if let Some(friendly_queen_location) = self.queen(self.to_move())
-                {
-                    let mut direct_drop = false;
-                    for direction in Direction::all()
-                    {
-                        let reference = destination + direction;
-                        if reference == friendly_queen_location
-                        {
-                            direct_drop = true;
-                            break;
-                        }
-                    }
-
-                    if direct_drop
-                    {
-                        break 'defense;
-                    }
-                }
-
-                // Otherwise, generate a full subtree.
-
-                moves = self.generate_moves(false);
+        let mut moves = Vec::new();
+        for mv in movements
+        {
+            if self.is_tactical(&mv) && !moves.contains(&mv)
+            {
+                moves.push(mv);
             }
         }
 
         moves
     }
+
+    /// Whether a movement or throw by the player to move changes how surrounded a queen is.
+    ///
+    /// That is a movement that fills a liberty of the enemy queen or vacates one of the friendly queen's, a throw to or
+    /// from next to a queen, or a Beetle (or Mosquito) climbing onto or off a queen. Placements are never tactical,
+    /// because they cannot touch the enemy queen.
+    pub fn is_tactical(&self, mv: &Move) -> bool
+    {
+        let Move::Move(piece, _) = mv
+        else
+        {
+            return false;
+        };
+
+        let (Some(from), Some(to)) = (self.location(piece), self.destination(mv))
+        else
+        {
+            return false;
+        };
+
+        let to_move = self.to_move();
+        let (enemy, friendly) = (self.queen(to_move.flip()), self.queen(to_move));
+        let liberty = |queen: Option<Hex>, hex: Hex| queen.is_some_and(|queen| hex::neighbours(queen).contains(&hex));
+        let on = |queen: Option<Hex>, hex: Hex| queen == Some(hex);
+
+        let climbs = on(enemy, to) || on(enemy, from) || on(friendly, to) || on(friendly, from);
+        let fills = liberty(enemy, to) && !liberty(enemy, from);
+        let vacates = liberty(friendly, from) && !liberty(friendly, to);
+
+        // Only a throw can move an enemy bug, so any of them next to a queen counts.
+        let throws = piece.player != to_move && [enemy, friendly].into_iter().any(|queen| liberty(queen, from) || liberty(queen, to));
+
+        climbs || fills || vacates || throws
+    }
 }
 
 impl Board
@@ -149,11 +249,7 @@ impl Board
             // Get the pieces from the indices.
             .map(|(i, on_board)| (Piece::from(i as u8), on_board))
             // Drop the pieces that are pinned, and ensure they're not stunned.
-            .filter_map(|(piece, on_board)| {
-                on_board
-                    .map(|loc| (!self.is_pinned(&piece) && self.stunned != Some(loc)).then_some(piece))
-                    .unwrap_or(None)
-            })
+            .filter_map(|(piece, on_board)| on_board.and_then(|_| self.is_free(&piece).then_some(piece)))
             // Only move pieces owned by the current player.
             .filter(|piece| piece.player == to_move)
             // Take uniques.
@@ -164,6 +260,36 @@ impl Board
         });
     }
 
+    /// The bugs of a player that are free to move, with where they are.
+    fn free_pieces(&self, player: Player) -> impl Iterator<Item = (Piece, Hex)> + '_
+    {
+        self.pieces
+            .iter()
+            .enumerate()
+            .filter_map(|(i, on_board)| on_board.map(|hex| (Piece::from(i as u8), hex)))
+            .filter(move |(piece, _)| piece.player == player && self.is_free(piece))
+    }
+
+    /// Collects the hexes at most the given number of steps away from any of the given hexes.
+    fn within(hexes: &[Hex], steps: usize) -> Collection
+    {
+        let mut near = Collection::new();
+        let mut frontier = hexes.to_vec();
+        frontier.iter().for_each(|hex| near.insert(*hex));
+
+        for _ in 0..steps
+        {
+            frontier = frontier
+                .into_iter()
+                .flat_map(hex::neighbours)
+                .filter(|hex| !near.contains(*hex))
+                .collect();
+            frontier.iter().for_each(|hex| near.insert(*hex));
+        }
+
+        near
+    }
+
     /// Generates placements for the player to move.
     pub(super) fn generate_placements_into(&self, standard_position: bool, moves: &mut Vec<Move>)
     {
@@ -324,7 +450,7 @@ impl Board
 impl Board
 {
     /// Generates ground crawls for the given piece.
-    fn generate_ground_crawls(&self, piece: &Piece, moves: &mut Vec<Move>)
+    fn generate_ground_crawls(&self, piece: &Piece, destinations: &mut Vec<Hex>)
     {
         let from = self.pieces[piece.index() as usize].unwrap();
         hex::neighbours(from)
@@ -337,49 +463,59 @@ impl Board
             .filter(|to| self.ensure_ground_movement_satisfied(from, **to))
             // Drop movements that violate freedom to move and constant contact.
             .filter(|to| self.ensure_crawl_satisfied(from, **to, false))
-            .for_each(|to| {
-                let reference = self.reference(piece, *to).unwrap();
-                moves.push(Move::Move(*piece, reference));
-            });
+            .for_each(|to| destinations.push(*to));
+    }
+
+    /// Whether a piece on the board is neither pinned (or covered) nor stunned, and so could move if it is its turn.
+    fn is_free(&self, piece: &Piece) -> bool
+    {
+        let location = self.pieces[piece.index() as usize];
+        location.is_some() && !self.is_pinned(piece) && self.stunned != location
     }
 
     /// Finds all of the ways this piece can move.
     pub(super) fn generate_moves_for(&self, piece: &Piece, moves: &mut Vec<Move>)
+    {
+        let mut destinations = Vec::new();
+        self.generate_destinations_for(piece, &mut destinations);
+
+        moves.extend(destinations.into_iter().map(|to| Move::Move(*piece, self.reference(piece, to).unwrap())));
+    }
+
+    /// Finds every hex this piece can move to, possibly more than once, without working out how to write the moves down.
+    fn generate_destinations_for(&self, piece: &Piece, destinations: &mut Vec<Hex>)
     {
         // This is a convenient way to enter the generation logic while allowing the Mosquito an easy way to recurse on its neighbours.
-        self.generate_moves_for_kind(piece, piece.kind, moves)
+        self.generate_moves_for_kind(piece, piece.kind, destinations)
     }
 
-    /// Generates all of the moves for the given piece as if it was acting as the given bug type.
-    fn generate_moves_for_kind(&self, piece: &Piece, kind: Bug, moves: &mut Vec<Move>)
+    /// Generates all of the destinations for the given piece as if it was acting as the given bug type.
+    fn generate_moves_for_kind(&self, piece: &Piece, kind: Bug, destinations: &mut Vec<Hex>)
     {
         match kind
         {
-            | Bug::Ant => self.generate_ant(piece, moves),
-            | Bug::Beetle => self.generate_beetle(piece, moves),
-            | Bug::Grasshopper => self.generate_grasshopper(piece, moves),
-            | Bug::Ladybug => self.generate_ladybug(piece, moves),
-            | Bug::Mosquito => self.generate_mosquito(piece, moves),
-            | Bug::Pillbug => self.generate_pillbug(piece, moves),
-            | Bug::Queen => self.generate_queen(piece, moves),
-            | Bug::Spider => self.generate_spider(piece, moves),
+            | Bug::Ant => self.generate_ant(piece, destinations),
+            | Bug::Beetle => self.generate_beetle(piece, destinations),
+            | Bug::Grasshopper => self.generate_grasshopper(piece, destinations),
+            | Bug::Ladybug => self.generate_ladybug(piece, destinations),
+            | Bug::Mosquito => self.generate_mosquito(piece, destinations),
+            | Bug::Pillbug => self.generate_pillbug(piece, destinations),
+            | Bug::Queen => self.generate_queen(piece, destinations),
+            | Bug::Spider => self.generate_spider(piece, destinations),
         }
     }
 
     /// Generates ant moves for the given piece.
-    fn generate_ant(&self, piece: &Piece, moves: &mut Vec<Move>)
+    fn generate_ant(&self, piece: &Piece, destinations: &mut Vec<Hex>)
     {
         let from = self.pieces[piece.index() as usize].unwrap();
         let reachable = self.field.find_crawls(from, None);
 
-        reachable.iter().filter(|to| **to != from).for_each(|to| {
-            let reference = self.reference(piece, *to).unwrap();
-            moves.push(Move::Move(*piece, reference));
-        });
+        destinations.extend(reachable.into_iter().filter(|to| *to != from));
     }
 
     /// Generates beetle moves for the given piece.
-    fn generate_beetle(&self, piece: &Piece, moves: &mut Vec<Move>)
+    fn generate_beetle(&self, piece: &Piece, destinations: &mut Vec<Hex>)
     {
         let from = self.pieces[piece.index() as usize].unwrap();
         hex::neighbours(from)
@@ -388,14 +524,11 @@ impl Board
             .filter(|to| self.reachable(piece, **to))
             // Drop movements that violate freedom to move and constant contact.
             .filter(|to| self.ensure_crawl_satisfied(from, **to, false))
-            .for_each(|to| {
-                let reference = self.reference(piece, *to).unwrap();
-                moves.push(Move::Move(*piece, reference));
-            });
+            .for_each(|to| destinations.push(*to));
     }
 
     /// Generates grasshopper jumps for the given piece.
-    fn generate_grasshopper(&self, piece: &Piece, moves: &mut Vec<Move>)
+    fn generate_grasshopper(&self, piece: &Piece, destinations: &mut Vec<Hex>)
     {
         let from = self.pieces[piece.index() as usize].unwrap();
         Direction::all().iter().for_each(|direction| {
@@ -413,13 +546,12 @@ impl Board
                 to = to + *direction;
             }
 
-            let reference = self.reference(piece, to).unwrap();
-            moves.push(Move::Move(*piece, reference));
+            destinations.push(to);
         });
     }
 
     /// Generates ladybug movements.
-    fn generate_ladybug(&self, piece: &Piece, moves: &mut Vec<Move>)
+    fn generate_ladybug(&self, piece: &Piece, destinations: &mut Vec<Hex>)
     {
         let from = self.pieces[piece.index() as usize].unwrap();
 
@@ -446,14 +578,11 @@ impl Board
             // Take uniques.
             .collect::<HashSet<Hex>>();
 
-        to.iter().for_each(|to| {
-            let reference = self.reference(piece, *to).unwrap();
-            moves.push(Move::Move(*piece, reference));
-        });
+        destinations.extend(to);
     }
 
     /// Recursively generates moves for the mosquito.
-    fn generate_mosquito(&self, piece: &Piece, moves: &mut Vec<Move>)
+    fn generate_mosquito(&self, piece: &Piece, destinations: &mut Vec<Hex>)
     {
         let from = self.pieces[piece.index() as usize].unwrap();
         let height = self.stacks[from as usize].height();
@@ -466,36 +595,33 @@ impl Board
                 .filter(|piece| piece.kind != Bug::Mosquito)
                 // Generate moves for each piece as if the mosquito stole its type.
                 .for_each(|moving_as| {
-                    self.generate_moves_for_kind(piece, moving_as.kind, moves);
+                    self.generate_moves_for_kind(piece, moving_as.kind, destinations);
                 });
         }
         else
         {
-            self.generate_beetle(piece, moves);
+            self.generate_beetle(piece, destinations);
         }
     }
 
     /// Generates standard crawls as if the piece was a Pillbug.
-    fn generate_pillbug(&self, piece: &Piece, moves: &mut Vec<Move>)
+    fn generate_pillbug(&self, piece: &Piece, destinations: &mut Vec<Hex>)
     {
-        self.generate_ground_crawls(piece, moves);
+        self.generate_ground_crawls(piece, destinations);
     }
 
     /// Generates standard crawls as if the piece was a Queen.
-    fn generate_queen(&self, piece: &Piece, moves: &mut Vec<Move>)
+    fn generate_queen(&self, piece: &Piece, destinations: &mut Vec<Hex>)
     {
-        self.generate_ground_crawls(piece, moves);
+        self.generate_ground_crawls(piece, destinations);
     }
 
     /// Generates spider moves for the given piece.
-    fn generate_spider(&self, piece: &Piece, moves: &mut Vec<Move>)
+    fn generate_spider(&self, piece: &Piece, destinations: &mut Vec<Hex>)
     {
         let from = self.pieces[piece.index() as usize].unwrap();
         let reachable = self.field.find_crawls(from, Some(3));
 
-        reachable.iter().filter(|to| **to != from).for_each(|to| {
-            let reference = self.reference(piece, *to).unwrap();
-            moves.push(Move::Move(*piece, reference));
-        });
+        destinations.extend(reachable.into_iter().filter(|to| *to != from));
     }
 }
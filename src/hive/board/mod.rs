@@ -6,13 +6,16 @@ mod checks;
 mod ensures;
 mod generate;
 mod history;
+mod pins;
 mod printers;
+mod resolved;
 mod state;
 mod token;
 mod zobrist;
 
 use history::Patch;
 pub use history::{Entry, History};
+pub use resolved::ResolvedMove;
 pub use state::GameState;
 pub use token::{Stack, Token};
 pub use zobrist::ZobristHash;
@@ -157,6 +160,21 @@ impl Board
         }
     }
 
+    /// Returns the hex a move ends on, provided all of the pieces it refers to are placed.
+    pub fn destination(&self, mv: &Move) -> Option<Hex>
+    {
+        match mv
+        {
+            | Move::Pass => None,
+            | Move::Place(_, nextto) => match nextto
+            {
+                | Some(n) if !self.placed(&n.piece) => None,
+                | _ => Some(self.resolve(nextto)),
+            },
+            | Move::Move(_, nextto) => self.placed(&nextto.piece).then(|| self.resolve(&Some(*nextto))),
+        }
+    }
+
     /// Returns the field of this hive.
     pub fn field(&self) -> &Field
     {
@@ -225,6 +243,22 @@ impl Board
         false
     }
 
+    /// Determines whether a move is valid in the current position.
+    ///
+    /// Unlike [Board::check()], this is safe to call on moves that came from somewhere else (such as a table or another
+    /// position), because it does not assume that the pieces the move refers to are in the hive. Passes are never
+    /// considered legal here, because they are only valid when there is no other move.
+    pub fn is_legal(&self, mv: &Move) -> bool
+    {
+        match mv
+        {
+            | Move::Pass => false,
+            | Move::Place(piece, _) if self.placed(piece) => false,
+            | Move::Move(piece, _) if !self.placed(piece) => false,
+            | _ => self.destination(mv).is_some() && self.check(mv).is_ok(),
+        }
+    }
+
     /// Determines if the given hex is pinned.
     pub fn is_pinned(&self, piece: &Piece) -> bool
     {
@@ -506,6 +540,7 @@ impl Board
         // This function only nominally does error checking. Because we are undoing a move that previously passed a
         // Board::check() in the play step, we skip error checking and use the unchecked versions of insert and remove.
         // If something goes wrong, that is *concerning*.
+        let occupancy = self.field.occupancy();
 
         match entry.mv
         {
@@ -528,7 +563,7 @@ impl Board
         };
 
         // Recalculate the pins.
-        self.pinned = self.field.find_pins();
+        self.refresh_pins(occupancy, None);
 
         // Find the last hex moved to (which might be None) and reset the immunity and stun states.
         self.undo_immune()?;
@@ -548,6 +583,12 @@ impl Board
     {
         self.zobrist.get()
     }
+
+    /// Gets a fingerprint of the zobrist keys used by every board, to check that saved keys are still meaningful.
+    pub fn zobrist_fingerprint() -> u64
+    {
+        ZobristTable::fingerprint()
+    }
 }
 
 /// Private implementation for this board.
@@ -747,6 +788,9 @@ impl Board
             prev_stunned: self.stunned,
         };
 
+        let occupancy = self.field.occupancy();
+        let size = self.field.len();
+
         match mv
         {
             | Move::Place(piece, nextto) =>
@@ -780,8 +824,13 @@ impl Board
             }
         };
 
-        // Recalculate the pins.
-        self.pinned = self.field.find_pins();
+        // Recalculate the pins. If the field grew, the only change was the destination becoming occupied.
+        let destination = match mv
+        {
+            | Move::Place(piece, _) | Move::Move(piece, _) => self.location(piece),
+            | Move::Pass => None,
+        };
+        self.refresh_pins(occupancy, destination.filter(|_| self.field.len() > size));
 
         // Update the history.
         self.history.play(entry);
@@ -795,7 +844,7 @@ impl Board
     /// Determines if the target hex is isolated when removing the given piece.
     fn reachable(&self, piece: &Piece, to: Hex) -> bool
     {
-        self.neighbours(to).into_iter().filter(|adj| *adj != *piece).count() > 0
+        hex::neighbours(to).into_iter().any(|adj| self.top(adj).is_some_and(|adj| adj != *piece))
     }
 
     #[allow(unused)]
@@ -861,11 +910,10 @@ impl Board
         self.zobrist.stun(self.stunned);
     }
 
-    /// Sets the immune hex to the last destination.
+    /// Sets the immune hex back to the destination of the move before the one being undone.
     fn undo_immune(&mut self) -> Result<()>
     {
-        let Some(entry) = self.history.prev()
-        else
+        if self.history.prev().is_none()
         {
             return Err(Error::new(
                 Kind::InternalError,
@@ -874,7 +922,8 @@ impl Board
         };
 
         // Update the immune hex and the Zobrist hash.
-        self.set_immune(entry.patch.map(|patch| patch.to));
+        let before = self.history.iter().rev().nth(1);
+        self.set_immune(before.and_then(|entry| entry.patch).map(|patch| patch.to));
 
         Ok(())
     }
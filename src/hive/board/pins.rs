@@ -0,0 +1,76 @@
+use std::cell::RefCell;
+
+use crate::prelude::*;
+
+/// The number of occupancies whose pins each thread remembers.
+const CACHE_SIZE: usize = 1 << 12;
+
+thread_local! {
+    /// Pins only depend on which hexes are occupied, and a search keeps revisiting the same handful of occupancies, so
+    /// each thread remembers the pins of the ones it saw recently.
+    ///
+    /// An empty field hashes to 0 and has no pins, so the empty slots are already correct.
+    static PIN_CACHE: RefCell<Vec<(u64, Collection)>> = RefCell::new(vec![(0, Collection::new()); CACHE_SIZE]);
+}
+
+impl Board
+{
+    /// Brings the pinned hexes up to date after the field changes, given the occupancy it had before.
+    ///
+    /// If the only change to the occupancy was a new hex being added, pass it as `added` so that it can be treated as
+    /// a pendant where possible. Otherwise, the pins are looked up by occupancy, and only recomputed on a miss.
+    pub(super) fn refresh_pins(&mut self, before: u64, added: Option<Hex>)
+    {
+        if self.field.occupancy() != before
+        {
+            self.pinned = match added.and_then(|hex| self.pendant_pins(hex))
+            {
+                | Some(pinned) => pinned,
+                | None => Self::cached_pins(&self.field),
+            };
+        }
+
+        debug_assert_eq!(self.pinned, self.field.find_pins(), "pins have diverged from the field");
+    }
+
+    /// Looks up the pins of a field by its occupancy, finding them from scratch on a miss.
+    fn cached_pins(field: &Field) -> Collection
+    {
+        let key = field.occupancy();
+
+        PIN_CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            let slot = &mut cache[key as usize & (CACHE_SIZE - 1)];
+
+            if slot.0 != key
+            {
+                *slot = (key, field.find_pins());
+            }
+
+            slot.1
+        })
+    }
+
+    /// Finds the pins after adding a hex, provided that it only touches one other hex.
+    ///
+    /// A pendant cannot join or split any part of the hive, so the old pins stay pinned and the only new pin is the hex
+    /// holding the pendant on, unless those two are all there is.
+    fn pendant_pins(&self, hex: Hex) -> Option<Collection>
+    {
+        let mut neighbours = hex::neighbours(hex).into_iter().filter(|neighbour| self.field.contains(*neighbour));
+
+        let anchor = neighbours.next()?;
+        if neighbours.next().is_some()
+        {
+            return None;
+        }
+
+        let mut pinned = self.pinned;
+        if self.field.len() > 2
+        {
+            pinned.insert(anchor);
+        }
+
+        Some(pinned)
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::HashSet;
+
+use crate::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// A move that has been resolved against a particular board into real coordinates.
+///
+/// Unlike a [Move], which only knows its destination relative to some reference piece, a resolved move knows where the
+/// piece comes from, where it goes, and at which heights.
+pub struct ResolvedMove
+{
+    /// The move that this resolution was derived from.
+    pub mv: Move,
+
+    /// The piece being placed or moved.
+    pub piece: Piece,
+
+    /// The hex the piece comes from, or None if it comes from the pouch.
+    pub from: Option<Axial>,
+
+    /// The height of the piece before moving, where 1 is ground level; None if it comes from the pouch.
+    pub from_height: Option<u8>,
+
+    /// The hex the piece ends up on.
+    pub to: Axial,
+
+    /// The height of the piece after moving, where 1 is ground level.
+    pub to_height: u8,
+
+    /// Whether this movement is only possible because a Pillbug (or a Mosquito acting as one) throws the piece.
+    pub throw: bool,
+}
+
+impl std::fmt::Display for ResolvedMove
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        let from = match (self.from, self.from_height)
+        {
+            | (Some(axial), Some(height)) => format!("{}@{}", axial, height),
+            | _ => "pouch".into(),
+        };
+        let throw = if self.throw { " (thrown)" } else { "" };
+        write!(f, "{: <3} from {} to {}@{}{}", format!("{}", self.piece), from, self.to, self.to_height, throw)
+    }
+}
+
+impl From<ResolvedMove> for Move
+{
+    fn from(value: ResolvedMove) -> Move
+    {
+        value.mv
+    }
+}
+
+impl From<ResolvedMove> for MoveString
+{
+    fn from(value: ResolvedMove) -> MoveString
+    {
+        value.mv.into()
+    }
+}
+
+impl ResolvedMove
+{
+    /// Whether this resolved move places a piece from the pouch.
+    pub fn is_placement(&self) -> bool
+    {
+        self.from.is_none()
+    }
+}
+
+impl Board
+{
+    /// Generates all valid moves in the position, not including Pass, resolved into real coordinates.
+    ///
+    /// Each move appears once; a movement is only marked as a throw if the piece could not have made it on its own.
+    pub fn legal_moves_resolved(&self, standard_position: bool) -> Vec<ResolvedMove>
+    {
+        let non_throws = self.generate_non_throws(standard_position);
+
+        let mut throws = Vec::new();
+        self.generate_throws_into(&mut throws);
+
+        let mut seen: HashSet<Move> = HashSet::new();
+        let mut resolved = Vec::with_capacity(non_throws.len() + throws.len());
+
+        for mv in non_throws
+        {
+            if seen.insert(mv)
+            {
+                resolved.push(self.resolve_as(&mv, false));
+            }
+        }
+
+        for mv in throws
+        {
+            if seen.insert(mv)
+            {
+                resolved.push(self.resolve_as(&mv, true));
+            }
+        }
+
+        resolved
+    }
+
+    /// Resolves an arbitrary move against this board, provided all of the pieces it refers to are placed.
+    ///
+    /// This does not check that the move is valid; see [Board::check()] for that.
+    pub fn resolve_move(&self, mv: &Move) -> Option<ResolvedMove>
+    {
+        match mv
+        {
+            | Move::Pass => None,
+            | Move::Place(_, nextto) =>
+            {
+                if nextto.map(|n| !self.placed(&n.piece)).unwrap_or(false)
+                {
+                    return None;
+                }
+                Some(self.resolve_as(mv, false))
+            }
+            | Move::Move(piece, nextto) =>
+            {
+                if !self.placed(piece) || !self.placed(&nextto.piece)
+                {
+                    return None;
+                }
+
+                let to = self.resolve(&Some(*nextto));
+                let throw = piece.player != self.to_move() || self.check_motion(piece, to).is_err();
+                Some(self.resolve_as(mv, throw))
+            }
+        }
+    }
+}
+
+impl Board
+{
+    /// Resolves a placement or movement whose references are known to be in the hive.
+    fn resolve_as(&self, mv: &Move, throw: bool) -> ResolvedMove
+    {
+        let (piece, from, to) = match mv
+        {
+            | Move::Place(piece, nextto) => (*piece, None, self.resolve(nextto)),
+            | Move::Move(piece, nextto) => (*piece, self.location(piece), self.resolve(&Some(*nextto))),
+            | Move::Pass => unreachable!(),
+        };
+
+        let from_height = from.map(|hex| self.stacks[hex as usize].height());
+        let to_height = self.stacks[to as usize].height() + 1;
+
+        ResolvedMove {
+            mv: *mv,
+            piece,
+            from: from.map(Axial::from),
+            from_height,
+            to: Axial::from(to),
+            to_height,
+            throw,
+        }
+    }
+}
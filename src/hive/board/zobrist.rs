@@ -13,7 +13,7 @@ const PIECES: usize = piece::consts::COUNT as usize;
 
 /// We need a bitstring for each piece on each hex at each height.
 const NUM_BITSTRINGS: usize = HEIGHTS * HEXES * PIECES;
-const BITSTRING_MASK: u128 = u16::MAX as u128;
+const BITSTRING_MASK: u128 = u64::MAX as u128;
 
 const OFFSET_LAST: usize = 0x40;
 const OFFSET_LAST_VALID: usize = 0x61;
@@ -57,6 +57,19 @@ pub struct ZobristTable
 
 impl ZobristTable
 {
+    /// Summarizes the bitstrings and layout used by these hashes, so that hashes saved by one build can be checked
+    /// before they are trusted by another.
+    pub fn fingerprint() -> u64
+    {
+        let layout = [NUM_BITSTRINGS, OFFSET_LAST, OFFSET_LAST_VALID, OFFSET_STUN, OFFSET_STUN_VALID, OFFSET_PLAYER];
+
+        layout
+            .iter()
+            .map(|n| *n as u64)
+            .chain(BITSTRINGS.iter().copied())
+            .fold(0xCBF2_9CE4_8422_2325, |acc, word| (acc ^ word).wrapping_mul(0x0000_0100_0000_01B3).rotate_left(7))
+    }
+
     /// Gets the hash associated with the current state.
     pub fn get(&self) -> ZobristHash
     {
@@ -97,6 +110,7 @@ impl ZobristTable
     /// Declares the player to move.
     pub fn player(&mut self, player: Player) -> &mut Self
     {
+        self.current &= !(EXTENT_PLAYER << OFFSET_PLAYER);
         self.current |= (player as u128) << OFFSET_PLAYER;
         self
     }
@@ -36,6 +36,6 @@ impl From<Axial> for Hex
 {
     fn from(value: Axial) -> Hex
     {
-        ROOT.wrapping_add(ROWS.wrapping_mul(value.q as Hex)).wrapping_add(value.r as Hex)
+        MASK & ROOT.wrapping_add(ROWS.wrapping_mul(value.r as Hex)).wrapping_add(value.q as Hex)
     }
 }
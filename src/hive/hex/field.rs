@@ -7,7 +7,8 @@ use crate::prelude::*;
 pub struct Field
 {
     map: HashMap<Hex, u8>,
-    markers: Collection
+    markers: Collection,
+    occupancy: u64,
 }
 
 impl FromIterator<Hex> for Field
@@ -17,14 +18,17 @@ impl FromIterator<Hex> for Field
         let map: HashMap<Hex, u8> = iter.into_iter().map(|h| (h, 1)).collect();
 
         let mut markers = Collection::default();
+        let mut occupancy = 0;
         for hex in map.keys()
         {
             markers.insert(*hex);
+            occupancy ^= Field::occupancy_key(*hex);
         }
 
         Field {
             map,
-            markers            
+            markers,
+            occupancy,
         }
     }
 }
@@ -233,7 +237,7 @@ impl Field
         }
         else
         {
-            false
+            true
         }
     }
 
@@ -298,7 +302,7 @@ impl Field
     /// Determines whether the hex is locked behind a gate.
     pub fn is_gated(&self, hex: Hex) -> bool
     {
-        self.neighbours(hex).len() >= 5
+        hex::neighbours(hex).iter().filter(|h| self.contains(**h)).count() >= 5
     }
 
     /// Gets the size of the field.
@@ -313,6 +317,14 @@ impl Field
         hex::neighbours(hex).into_iter().filter(|hex| self.contains(*hex)).collect()
     }
 
+    /// Returns a hash of which hexes are occupied, ignoring the heights of the stacks on them.
+    ///
+    /// Two fields with the same occupancy have the same pins.
+    pub fn occupancy(&self) -> u64
+    {
+        self.occupancy
+    }
+
     /// Returns the field consisting of the perimeter.
     pub fn perimeter(&self, as_if_without: Option<Hex>) -> Perimeter
     {
@@ -341,6 +353,7 @@ impl Field
             {
                 o.remove_entry();
                 self.markers.remove(hex);
+                self.occupancy ^= Self::occupancy_key(hex);
             }
             else
             {
@@ -360,10 +373,24 @@ impl Field
         {    
             self.map.insert(hex, 1u8);
             self.markers.insert(hex);
+            self.occupancy ^= Self::occupancy_key(hex);
         }
     }
 }
 
+impl Field
+{
+    /// Scrambles a hex into the key it contributes to the occupancy hash.
+    fn occupancy_key(hex: Hex) -> u64
+    {
+        // This is the SplitMix64 finalizer, which is plenty for a fixed set of 1024 keys.
+        let mut z = (hex as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
 // An implementation Tarjan's algorithm for finding articulation points.
 
 #[derive(Clone)]
@@ -419,7 +446,7 @@ impl Field
         state.count += 1;
 
         let mut children = 0;
-        for neighbour in self.neighbours(hex)
+        for neighbour in hex::neighbours(hex).into_iter().filter(|neighbour| self.contains(*neighbour))
         {
             if let Some(par) = parent
             {
@@ -542,11 +569,11 @@ impl Perimeter
         }
         else
         {
-            for neighbour in self.0.neighbours(hex)
+            for neighbour in hex::neighbours(hex).into_iter().filter(|h| self.0.contains(*h))
             {
                 if state.visited.contains(&neighbour)
-                    || self.1.ensure_freedom_to_move(hex, neighbour, false).is_err()
-                    || self.1.ensure_constant_contact(hex, neighbour, false).is_err()
+                    || !self.1.ensure_freedom_to_move_satisfied(hex, neighbour, false)
+                    || !self.1.ensure_constant_contact_satisfied(hex, neighbour, false)
                 {
                     // The original field forms a gate here.
                     continue;
@@ -565,11 +592,11 @@ impl Perimeter
     {
         state.visited.insert(from);
 
-        for neighbour in self.0.neighbours(from)
+        for neighbour in hex::neighbours(from).into_iter().filter(|h| self.0.contains(*h))
         {
             if !state.visited.contains(&neighbour)
-                && self.1.ensure_freedom_to_move(from, neighbour, false).is_ok()
-                && self.1.ensure_constant_contact(from, neighbour, false).is_ok()
+                && self.1.ensure_freedom_to_move_satisfied(from, neighbour, false)
+                && self.1.ensure_constant_contact_satisfied(from, neighbour, false)
             {
                 self.reachable_recurse(neighbour, state);
             }
@@ -4,7 +4,7 @@ pub(crate) mod notation;
 pub(crate) mod options;
 pub mod piece;
 
-pub use board::{Board, GameState, Token, ZobristHash};
+pub use board::{Board, GameState, ResolvedMove, Token, ZobristHash};
 pub use hex::{Axial, Collection, Direction, Field, Hex, Perimeter};
 pub use notation::types::*;
 pub use options::*;
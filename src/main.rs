@@ -8,7 +8,7 @@ fn main() -> Result<()>
     dotenvy::dotenv().ok();
 
     // Parse the cli options.
-    let options = UhpOptions::parse();
+    let mut options = UhpOptions::parse();
 
     // Choose the logging type based on build type.
     let log_format = if cfg!(debug_assertions)
@@ -31,8 +31,15 @@ fn main() -> Result<()>
     // Display the server package information.
     print_header();
 
+    // Load any weights before the evaluator is created.
+    if let Err(e) = options.load_weights()
+    {
+        log::error!("fatal error: {}", e);
+        return Ok(());
+    }
+
     // Run the main UHP loop.
-    if let Err(e) = Server::<evaluators::Strongest>::new(options).run()
+    if let Err(e) = Server::<evaluators::Dynamic>::new(options).run()
     {
         log::error!("fatal error: {}", e);
     }
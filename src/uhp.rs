@@ -7,13 +7,17 @@ use crate::prelude::*;
 pub struct UhpOptions
 {
     #[arg(long, default_value_t = 1.0)]
-    /// maximum memory in GB for LFU
+    /// maximum memory in GB for the evaluation and move cache
     pub cache_memory: f64,
 
     #[arg(long, default_value_t = 1.0)]
     /// maximum memory in GB for transpositions
     pub table_memory: f64,
 
+    #[arg(short, long, value_enum, default_value_t)]
+    /// evaluator to search with
+    pub evaluator: evaluators::EvaluatorKind,
+
     #[arg(short, long, default_value = "info")]
     /// lowest log level to show
     pub log_level: String,
@@ -21,13 +25,176 @@ pub struct UhpOptions
     #[arg(short, long, default_value_t = 4)]
     /// number of search threads
     pub num_threads: usize,
+
+    #[arg(long = "no-pruning", action = clap::ArgAction::SetFalse)]
+    /// turn off late-move reductions, futility pruning and razoring in the alpha-beta search
+    pub pruning: bool,
+
+    #[arg(long, default_value_t = evaluators::Skill::MAX)]
+    /// strength of the alpha-beta evaluators, from 0 up to full strength at 20
+    pub skill_level: u8,
+
+    #[arg(long)]
+    /// file to append per-search statistics to, as JSON lines
+    pub telemetry: Option<std::path::PathBuf>,
+
+    #[arg(long, value_name = "FILE")]
+    /// evaluation weights to load, from a JSON or TOML file
+    pub eval_params: Option<std::path::PathBuf>,
+
+    #[arg(skip)]
+    /// the evaluation weights, which can also be changed one at a time through the `options` command
+    pub eval: evaluators::EvalParams,
+
+    #[arg(long, value_name = "FILE")]
+    /// feature weights for the linear evaluator to load, from a JSON or TOML file
+    pub linear_weights: Option<std::path::PathBuf>,
+
+    #[arg(skip)]
+    /// the feature weights for the linear evaluator
+    pub linear: evaluators::LinearWeights,
+
+    #[arg(long, value_enum, default_value_t)]
+    /// how the Monte-Carlo evaluator picks moves when it plays out positions
+    pub playout: evaluators::PlayoutPolicy,
+}
+
+impl UhpOptions
+{
+    /// The engine options that can be read and changed through the `options` command. The evaluation weights can be
+    /// too; see [`UhpOptions::names`].
+    pub const NAMES: [&'static str; 6] = ["CacheMemory", "Evaluator", "NumThreads", "Pruning", "SkillLevel", "TableMemory"];
+
+    /// The bounds on the memory options, in GB.
+    const MEMORY_RANGE: (f64, f64) = (0.001, 1024.0);
+
+    /// The bounds on the number of search threads.
+    const THREAD_RANGE: (usize, usize) = (1, 256);
+
+    /// The bounds on the skill level.
+    const SKILL_RANGE: (u8, u8) = (0, evaluators::Skill::MAX);
+
+    /// The bounds on an evaluation weight.
+    const WEIGHT_RANGE: (f64, f64) = (-1000.0, 1000.0);
+
+    /// Describes an option in the form `name;type;value;default;min;max`.
+    pub fn describe(&self, name: &str) -> Result<String>
+    {
+        let defaults = UhpOptions::parse_from(["hivemind"]);
+        let (min, max) = Self::MEMORY_RANGE;
+
+        let description = match name
+        {
+            | "CacheMemory" => format!("{name};double;{};{};{min};{max}", self.cache_memory, defaults.cache_memory),
+            | "Evaluator" =>
+            {
+                let kinds = evaluators::EvaluatorKind::all().map(|kind| kind.name()).join(";");
+                format!("{name};enum;{};{};{kinds}", self.evaluator.name(), defaults.evaluator.name())
+            }
+            | "NumThreads" =>
+            {
+                let (min, max) = Self::THREAD_RANGE;
+                format!("{name};int;{};{};{min};{max}", self.num_threads, defaults.num_threads)
+            }
+            | "Pruning" => format!("{name};bool;{};{}", self.pruning, defaults.pruning),
+            | "SkillLevel" =>
+            {
+                let (min, max) = Self::SKILL_RANGE;
+                format!("{name};int;{};{};{min};{max}", self.skill_level, defaults.skill_level)
+            }
+            | "TableMemory" => format!("{name};double;{};{};{min};{max}", self.table_memory, defaults.table_memory),
+            | _ =>
+            {
+                let value = self.eval.get(name).ok_or_else(|| Self::unknown(name))?;
+                let default = defaults.eval.get(name).unwrap_or_default();
+                let (min, max) = Self::WEIGHT_RANGE;
+                format!("{name};double;{value};{default};{min};{max}")
+            }
+        };
+
+        Ok(description)
+    }
+
+    /// Replaces the evaluation and feature weights with the ones in the `eval_params` and `linear_weights` files, if
+    /// there are any.
+    pub fn load_weights(&mut self) -> Result<()>
+    {
+        if let Some(path) = &self.eval_params
+        {
+            self.eval = evaluators::EvalParams::load(path)?;
+        }
+
+        if let Some(path) = &self.linear_weights
+        {
+            self.linear = evaluators::LinearWeights::load(path)?;
+        }
+
+        Ok(())
+    }
+
+    /// The names of every option that can be read and changed through the `options` command.
+    pub fn names() -> impl Iterator<Item = &'static str>
+    {
+        Self::NAMES.into_iter().chain(evaluators::EvalParams::NAMES)
+    }
+
+    /// The skill level of the alpha-beta evaluators.
+    pub fn skill(&self) -> evaluators::Skill
+    {
+        evaluators::Skill::new(self.skill_level)
+    }
+
+    /// Sets an option from its string value, checking that it is in range.
+    pub fn set(&mut self, name: &str, value: &str) -> Result<()>
+    {
+        match name
+        {
+            | "CacheMemory" => self.cache_memory = Self::parse_in_range(value, Self::MEMORY_RANGE)?,
+            | "Evaluator" => self.evaluator = value.parse()?,
+            | "NumThreads" => self.num_threads = Self::parse_in_range(value, Self::THREAD_RANGE)?,
+            | "Pruning" => self.pruning = value.parse().map_err(|_| Error::for_parse::<bool>(value.into()))?,
+            | "SkillLevel" => self.skill_level = Self::parse_in_range(value, Self::SKILL_RANGE)?,
+            | "TableMemory" => self.table_memory = Self::parse_in_range(value, Self::MEMORY_RANGE)?,
+            | _ =>
+            {
+                let weight = self.eval.get_mut(name).ok_or_else(|| Self::unknown(name))?;
+                *weight = Self::parse_in_range(value, Self::WEIGHT_RANGE)?;
+            }
+        };
+
+        Ok(())
+    }
+}
+
+impl UhpOptions
+{
+    /// Parses an option value, rejecting it if it falls outside the given bounds.
+    fn parse_in_range<T>(value: &str, (min, max): (T, T)) -> Result<T>
+    where
+        T: Copy + std::str::FromStr + PartialOrd + std::fmt::Display,
+    {
+        let parsed = value.parse::<T>().map_err(|_| Error::for_parse::<T>(value.into()))?;
+
+        if !(min..=max).contains(&parsed)
+        {
+            let msg = format!("{} is out of range ({} to {}).", value, min, max);
+            return Err(Error::new(Kind::InvalidOption, msg));
+        }
+
+        Ok(parsed)
+    }
+
+    /// Creates the error for an option that does not exist.
+    fn unknown(name: &str) -> Error
+    {
+        Error::new(Kind::InvalidOption, format!("'{}' is not a known option.", name))
+    }
 }
 
 pub struct Server<E>
 where
     E: Evaluator,
 {
-    #[allow(unused)]
     options:   UhpOptions,
     board:     Option<Board>,
     evaluator: E,
@@ -72,12 +239,17 @@ impl<E: Evaluator> Server<E>
         let result = match cmd
         {
             | "" => Ok(()),
+            | "bench" => self.bench(args),
             | "bestmove" => self.best_move(args),
+            | "clearhash" => self.clear_hash(),
+            | "eval" => self.eval(args),
             | "info" => self.info(),
+            | "loadtable" => self.load_table(args),
             | "newgame" => self.new_game(args),
             | "options" => self.options(args),
             | "pass" => self.play_move(&["pass"]),
             | "play" => self.play_move(args),
+            | "savetable" => self.save_table(args),
             | "undo" => self.undo(args),
             | "validmoves" => self.valid_moves(),
             | _ => Err(Error::new(Kind::UnrecognizedCommand, cmd.into())),
@@ -106,6 +278,131 @@ impl<E: Evaluator> Server<E>
         }
     }
 
+    /// Searches the benchmark positions to a fixed depth (3 by default), and prints the node counts and speeds.
+    ///
+    /// With `bench threads [seconds]`, instead searches each position for a fixed time (1 second by default) with 1, 2,
+    /// 4 and 8 threads, and prints how deep each got. With `bench pruning [depth]`, runs the benchmark with pruning
+    /// turned off and then on, and compares the node counts. With `bench tactics [depth]`, searches the tactical
+    /// positions with pruning turned off and then on, and prints which were solved.
+    ///
+    /// This is not part of the UHP specification.
+    fn bench(&mut self, args: &[&str]) -> Result<()>
+    {
+        match args.first()
+        {
+            | Some(&"threads") => return self.bench_threads(&args[1..]),
+            | Some(&"pruning") => return self.bench_pruning(&args[1..]),
+            | Some(&"tactics") => return self.bench_tactics(&args[1..]),
+            | _ => (),
+        }
+
+        let depth = Self::bench_depth(args)?;
+
+        let print = |i: usize, report: &bench::BenchReport| {
+            println!(
+                "position {} nodes {} time {} nps {:.0}",
+                i + 1,
+                report.nodes,
+                report.elapsed.as_millis(),
+                report.nodes_per_second()
+            );
+        };
+
+        let total = bench::bench(&mut self.evaluator, Depth::from(depth), print)?;
+        println!(
+            "total nodes {} time {} nps {:.0}",
+            total.nodes,
+            total.elapsed.as_millis(),
+            total.nodes_per_second()
+        );
+        Ok(())
+    }
+
+    /// Gets the depth argument of `bench`, which is 3 by default.
+    fn bench_depth(args: &[&str]) -> Result<u8>
+    {
+        match args.first()
+        {
+            | Some(arg) => arg.parse::<u8>().map_err(|_| Error::for_parse::<u8>((*arg).into())),
+            | None => Ok(3),
+        }
+    }
+
+    /// Searches the benchmark positions to a fixed depth with pruning turned off and then on, and prints how many nodes
+    /// pruning saved.
+    fn bench_pruning(&mut self, args: &[&str]) -> Result<()>
+    {
+        let depth = Self::bench_depth(args)?;
+
+        let mut nodes = [0; 2];
+        for (i, pruning) in [false, true].into_iter().enumerate()
+        {
+            let mut options = self.options.clone();
+            options.pruning = pruning;
+            self.evaluator.set_options(&options);
+
+            let total = bench::bench(&mut self.evaluator, Depth::from(depth), |_, _| {})?;
+            println!(
+                "pruning {} nodes {} time {} nps {:.0}",
+                pruning,
+                total.nodes,
+                total.elapsed.as_millis(),
+                total.nodes_per_second()
+            );
+            nodes[i] = total.nodes;
+        }
+        self.evaluator.set_options(&self.options);
+
+        println!("saved {:.1}%", 100.0 * (1.0 - nodes[1] as f64 / nodes[0].max(1) as f64));
+        Ok(())
+    }
+
+    /// Searches the tactical positions to a fixed depth, or deeper for those that need it, with pruning turned off and
+    /// then on, and prints the moves found and how many positions each solved.
+    fn bench_tactics(&mut self, args: &[&str]) -> Result<()>
+    {
+        let depth = Self::bench_depth(args)?;
+
+        for pruning in [false, true]
+        {
+            let mut options = self.options.clone();
+            options.pruning = pruning;
+            self.evaluator.set_options(&options);
+
+            let print = |i: usize, mv: &str, solves: bool| println!("position {} move {} solved {}", i + 1, mv, solves);
+            let solved = bench::tactics(&mut self.evaluator, Depth::from(depth), print)?;
+            println!("pruning {} solved {}/{}", pruning, solved, bench::TACTICS.len());
+        }
+        self.evaluator.set_options(&self.options);
+
+        Ok(())
+    }
+
+    /// Searches the benchmark positions with different numbers of threads, and prints how the search scales.
+    fn bench_threads(&mut self, args: &[&str]) -> Result<()>
+    {
+        let seconds = match args.first()
+        {
+            | Some(arg) => arg.parse::<u64>().map_err(|_| Error::for_parse::<u64>((*arg).into()))?,
+            | None => 1,
+        };
+        let time = std::time::Duration::from_secs(seconds);
+
+        let print = |report: &bench::ScalingReport| {
+            println!(
+                "threads {} depth {:.2} nps {:.0} agreed {}/{}",
+                report.threads,
+                report.depth,
+                report.nodes_per_second(time),
+                report.agreed,
+                bench::POSITIONS.len()
+            );
+        };
+
+        bench::scaling(&mut self.evaluator, &self.options, &[1, 2, 4, 8], time, print)?;
+        Ok(())
+    }
+
     /// Returns the best move available in this position (for the player to move).
     fn best_move(&mut self, args: &[&str]) -> Result<()>
     {
@@ -117,6 +414,15 @@ impl<E: Evaluator> Server<E>
         Ok(())
     }
 
+    /// Forgets every search result and cached position.
+    ///
+    /// This is not part of the UHP specification.
+    fn clear_hash(&mut self) -> Result<()>
+    {
+        self.evaluator.clear();
+        Ok(())
+    }
+
     /// Ensures there is a board loaded on this server.
     fn ensure_started(&self) -> Result<&Board>
     {
@@ -144,6 +450,28 @@ impl<E: Evaluator> Server<E>
         self.ok()
     }
 
+    /// Prints every term of the static evaluation of the current position, as a table or, with `eval json`, as JSON.
+    ///
+    /// This is not part of the UHP specification.
+    fn eval(&self, args: &[&str]) -> Result<()>
+    {
+        let board = self.ensure_started()?;
+        let breakdown = evaluators::Strongest::breakdown(&self.options.eval, board);
+
+        match args
+        {
+            | [] => println!("{}", breakdown),
+            | ["json"] =>
+            {
+                let json = serde_json::to_string(&breakdown).map_err(|err| Error::new(Kind::InternalError, err.to_string()))?;
+                println!("{}", json);
+            }
+            | _ => return Err(Error::new(Kind::ParseError, format!("'{}' is not a valid eval command.", args.join(" ")))),
+        }
+
+        Ok(())
+    }
+
     /// Prints the server's ID.
     fn info(&self) -> Result<()>
     {
@@ -151,12 +479,24 @@ impl<E: Evaluator> Server<E>
             "id {} v{} [using eval::{}]",
             env!("CARGO_PKG_NAME"),
             env!("CARGO_PKG_VERSION"),
-            Error::type_name::<E>()
+            self.evaluator.name()
         );
         println!("{};{};{}", Bug::Ladybug.long(), Bug::Mosquito.long(), Bug::Pillbug.long());
         Ok(())
     }
 
+    /// Restores a transposition table saved with `savetable`, to resume an analysis.
+    ///
+    /// This is not part of the UHP specification.
+    fn load_table(&mut self, args: &[&str]) -> Result<()>
+    {
+        let path = Self::table_path(args)?;
+        let count = self.evaluator.load_table(&path)?;
+
+        println!("loaded {} entries from {}", count, path.display());
+        Ok(())
+    }
+
     /// Creates a new game with the given options string.
     fn new_game(&mut self, args: &[&str]) -> Result<()>
     {
@@ -170,6 +510,8 @@ impl<E: Evaluator> Server<E>
             self.board = Some(Board::default())
         }
 
+        self.evaluator.new_game();
+
         let board = self.ensure_started()?;
         println!("{}", GameString::from(board));
         Ok(())
@@ -183,9 +525,37 @@ impl<E: Evaluator> Server<E>
     }
 
     /// Implements the optionsmap interface for this server.
-    fn options(&mut self, _args: &[&str]) -> Result<()>
+    ///
+    /// Changing a memory option reallocates (and so empties) the corresponding table.
+    fn options(&mut self, args: &[&str]) -> Result<()>
     {
-        Ok(())
+        match args
+        {
+            | [] =>
+            {
+                for name in UhpOptions::names()
+                {
+                    self.print_option(name)?;
+                }
+                Ok(())
+            }
+            | ["get", name] => self.print_option(name),
+            | ["set", name, value] =>
+            {
+                let mut options = self.options.clone();
+                options.set(name, value)?;
+
+                self.evaluator.set_options(&options);
+                self.options = options;
+
+                log::info!("evaluator memory: {:.1} MB", self.evaluator.memory() as f64 / 1e+6);
+                self.print_option(name)
+            }
+            | _ => Err(Error::new(
+                Kind::ParseError,
+                format!("'{}' is not a valid options command.", args.join(" ")),
+            )),
+        }
     }
 
     /// Plays the given move on the current board, if one exists.
@@ -205,6 +575,36 @@ impl<E: Evaluator> Server<E>
         Ok(())
     }
 
+    /// Saves the transposition table to a file, so that an analysis can be resumed later with `loadtable`.
+    ///
+    /// This is not part of the UHP specification.
+    fn save_table(&self, args: &[&str]) -> Result<()>
+    {
+        let path = Self::table_path(args)?;
+        let count = self.evaluator.save_table(&path)?;
+
+        println!("saved {} entries to {}", count, path.display());
+        Ok(())
+    }
+
+    /// Prints an option in the form `name;type;value;default;min;max`.
+    fn print_option(&self, name: &str) -> Result<()>
+    {
+        println!("{}", self.options.describe(name)?);
+        Ok(())
+    }
+
+    /// Gets the path argument of `savetable` and `loadtable`, which may contain spaces.
+    fn table_path(args: &[&str]) -> Result<std::path::PathBuf>
+    {
+        if args.is_empty()
+        {
+            return Err(Error::new(Kind::ParseError, "You must provide a path to the table file.".into()));
+        }
+
+        Ok(args.join(" ").into())
+    }
+
     #[allow(unused)]
     /// Placeholder for unimplemented features.
     fn todo(&self) -> Result<()>
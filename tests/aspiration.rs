@@ -0,0 +1,99 @@
+mod common;
+use common::*;
+
+#[cfg(test)]
+mod aspiration
+{
+    use hivemind::prelude::*;
+
+    use super::*;
+
+    /// Gets the last iteration of a search, and the window it first searched inside, which is centred on the score of
+    /// the iteration before it.
+    fn last_window(record: &serde_json::Value) -> (&serde_json::Value, i64, i64)
+    {
+        let iterations = record["iterations"].as_array().unwrap();
+        let [previous, last] = &iterations[iterations.len() - 2..]
+        else
+        {
+            panic!("only {} iterations", iterations.len());
+        };
+
+        let window = last["window"].as_array().unwrap();
+        let (a, b) = (window[0].as_i64().unwrap(), window[1].as_i64().unwrap());
+        assert!(a < previous["score"].as_i64().unwrap() && previous["score"].as_i64().unwrap() < b);
+
+        (last, a, b)
+    }
+
+    /// Checks that the move played leads the principal variation, and that the score reported is the last iteration's.
+    fn assert_reported(board: &Board, mv: Move, record: &serde_json::Value)
+    {
+        let pv = record["pv"].as_array().unwrap();
+        assert_eq!(Move::from(&pv[0].as_str().unwrap().parse().unwrap(), board).unwrap(), mv);
+
+        let iterations = record["iterations"].as_array().unwrap();
+        assert_eq!(record["score"], iterations.last().unwrap()["score"]);
+    }
+
+    #[test]
+    fn aspiration_windows_are_recorded()
+    {
+        let _setup = setup::setup();
+
+        let raw_string = r"Base;InProgress;White[4];wA1;bA1 wA1-;wQ -wA1;bQ bA1-;wB1 -wQ;bB1 bQ-";
+        let board = templates::run_game(raw_string);
+        let (_, record) = templates::search_with_telemetry("windows", &board, Depth::new(3));
+
+        // Every iteration from the second on searches inside a window, however often it has to widen.
+        let aspiration = &record["aspiration"];
+        assert_eq!(aspiration["windows"], 2);
+        assert!(aspiration["fail_lows"].is_u64() && aspiration["fail_highs"].is_u64());
+
+        let windows = record["iterations"].as_array().unwrap().iter().map(|iteration| iteration["window"].is_array());
+        assert_eq!(windows.collect::<Vec<_>>(), [false, true, true]);
+    }
+
+    #[test]
+    fn aspiration_fails_high_on_a_win_found_deeper()
+    {
+        let _setup = setup::setup();
+
+        // The first ply sees nothing special, so the window around its score cannot hold the win the second ply finds.
+        let tactic = &bench::TACTICS[1];
+        let board = templates::run_game(tactic.position);
+        let (mv, record) = templates::search_with_telemetry("fail-high", &board, Depth::new(2));
+
+        let aspiration = &record["aspiration"];
+        assert_eq!(aspiration["windows"], 1);
+        assert_eq!(aspiration["fail_highs"], 1);
+        assert_eq!(aspiration["fail_lows"], 0);
+
+        let (last, _, b) = last_window(&record);
+        assert!(last["score"].as_i64().unwrap() >= b);
+        assert_reported(&board, mv, &record);
+
+        assert!(tactic.solutions.iter().any(|solution| Move::from(&solution.parse().unwrap(), &board).unwrap() == mv));
+        assert_eq!(scores::reconstruct(record["score"].as_i64().unwrap() as i32), MINIMUM_WIN);
+    }
+
+    #[test]
+    fn aspiration_fails_low_when_the_score_drops()
+    {
+        let _setup = setup::setup();
+
+        // White looks well off one ply deep, but the second finds Black's reply and the score falls through the window.
+        let board = templates::run_game(bench::POSITIONS[0]);
+        let (mv, record) = templates::search_with_telemetry("fail-low", &board, Depth::new(2));
+
+        let aspiration = &record["aspiration"];
+        assert_eq!(aspiration["windows"], 1);
+        assert_eq!(aspiration["fail_lows"], 1);
+        assert_eq!(aspiration["fail_highs"], 0);
+
+        // The widened search is the iteration's answer, so it is what gets played and reported.
+        let (last, a, _) = last_window(&record);
+        assert!(last["score"].as_i64().unwrap() <= a);
+        assert_reported(&board, mv, &record);
+    }
+}
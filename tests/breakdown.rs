@@ -0,0 +1,100 @@
+mod common;
+use common::*;
+
+#[cfg(test)]
+mod breakdown
+{
+    use hivemind::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn breakdown_adds_up_to_the_evaluation()
+    {
+        let _setup = setup::setup();
+
+        let params = evaluators::EvalParams::default();
+
+        for position in bench::POSITIONS
+        {
+            let board = templates::run_game(position);
+            let breakdown = evaluators::Strongest::breakdown(&params, &board);
+
+            let (mine, theirs) = match board.to_move()
+            {
+                | Player::White => (&breakdown.white, &breakdown.black),
+                | Player::Black => (&breakdown.black, &breakdown.white),
+            };
+
+            assert_eq!(breakdown.score, evaluators::Strongest::evaluate_board(&params, &board));
+            assert_eq!(breakdown.score, (mine.total() - theirs.total()).floor() as i32);
+
+            let material = mine.pieces.iter().map(|p| p.score).sum::<f64>();
+            assert_eq!(mine.material, material);
+            let mobility = mine.pieces.iter().map(|p| p.mobility).sum::<f64>();
+            assert_eq!(mine.mobility, mobility);
+            assert!(mine.pieces.iter().filter(|p| p.pinned).all(|p| p.score == 0.0));
+        }
+    }
+
+    #[test]
+    fn race_counts_the_tempi_to_each_killspot()
+    {
+        let _setup = setup::setup();
+
+        let params = evaluators::EvalParams::default();
+
+        // The tempi to each open killspot of the white and the black queen, as worked out by hand, and how many more tempi
+        // the opponent of the player to move needs than they do.
+        let cases: [(&str, &[usize], &[usize], i32); 2] = [
+            // The bugs lie in a row, wG2 wQ wG1 bG1 bQ, so everything but the two ends is pinned. wG2 jumps the row to the
+            // killspot east of bQ, and the queens cannot crawl next to each other, so every other killspot needs an Ant.
+            (r"Base;InProgress;Black[3];wG1;bG1 wG1-;wQ -wG1;bQ bG1-;wG2 -wQ", &[2, 2, 2, 2], &[1, 2, 2, 2, 2], 1),
+            // White's bugs wind around a hole east of wQ, which has five neighbours and so is gated, and is capped at three.
+            // Neither free Black bug gets near wQ, and wG2 only jumps away from bQ.
+            (
+                r"Base;InProgress;Black[5];wG1;bG1 \wG1;wQ /wG1;bQ \bG1;wB1 wG1-;bG2 -bQ;wS1 wB1\;bB1 bQ/;wG2 /wS1",
+                &[3, 2, 2, 2, 2],
+                &[2, 2, 2],
+                -5,
+            ),
+        ];
+
+        for (position, white, black, race) in cases
+        {
+            let board = templates::run_game(position);
+            let breakdown = evaluators::Strongest::breakdown(&params, &board);
+            let k_race = params.tapered(breakdown.phase).k_race;
+
+            let tempi = |tempi: &[usize]| k_race * tempi.iter().sum::<usize>() as f64;
+            assert_eq!(breakdown.white.race, tempi(white), "in {}", position);
+            assert_eq!(breakdown.black.race, tempi(black), "in {}", position);
+
+            let (mine, theirs) = match board.to_move()
+            {
+                | Player::White => (&breakdown.white, &breakdown.black),
+                | Player::Black => (&breakdown.black, &breakdown.white),
+            };
+            assert_eq!(((mine.race - theirs.race) / k_race).round() as i32, race, "in {}", position);
+        }
+    }
+
+    #[test]
+    fn breakdown_serializes_every_term()
+    {
+        let _setup = setup::setup();
+
+        let board = templates::run_game(bench::POSITIONS[0]);
+        let breakdown = evaluators::Strongest::breakdown(&evaluators::EvalParams::default(), &board);
+
+        let json: serde_json::Value = serde_json::from_str(&serde_json::to_string(&breakdown).unwrap()).unwrap();
+        for term in ["material", "mobility", "pieces", "queens", "race", "reserve"]
+        {
+            assert!(json["white"].get(term).is_some() && json["black"].get(term).is_some());
+        }
+        assert!(json["white"]["queen"].get("killspots").is_some());
+
+        let table = breakdown.to_string();
+        assert!(table.contains("material") && table.contains("mobility") && table.contains("pillbug escapes"));
+    }
+}
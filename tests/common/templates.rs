@@ -1,5 +1,34 @@
+use clap::Parser;
 use hivemind::prelude::*;
 
+/// Options for a small single-threaded search, with tiny tables, and any extra arguments on top.
+#[allow(dead_code)]
+pub fn options(args: &[&str]) -> UhpOptions
+{
+    let base = ["hivemind", "--num-threads", "1", "--table-memory", "0.01", "--cache-memory", "0.01"];
+    UhpOptions::parse_from(base.iter().chain(args))
+}
+
+/// Searches a board to a depth with the strongest evaluator, with the options from [`options`], and returns the move
+/// found along with the telemetry record of the search, which goes through a temporary file named after the test.
+#[allow(dead_code)]
+pub fn search_with_telemetry(test: &str, board: &Board, depth: Depth) -> (Move, serde_json::Value)
+{
+    let path = std::env::temp_dir().join(format!("hivemind-{}-{}.jsonl", test, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut evaluator = evaluators::Strongest::new(options(&["--telemetry", path.to_str().unwrap()]));
+    let mv = evaluator.best_move(board, SearchArgs::Depth(depth));
+    assert!(board.is_legal(&mv));
+    drop(evaluator);
+
+    let line = std::fs::read_to_string(&path).unwrap();
+    let record: serde_json::Value = serde_json::from_str(line.trim()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    (mv, record)
+}
+
 /// Runs a game, and checks for both gamestring validity and state mismatch.
 pub fn run_game(raw_string: &'static str) -> Board
 {
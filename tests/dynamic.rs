@@ -0,0 +1,42 @@
+mod common;
+use common::*;
+
+#[cfg(test)]
+mod dynamic
+{
+    use clap::Parser;
+    use hivemind::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn evaluator_is_chosen_by_option()
+    {
+        let _setup = setup::setup();
+
+        let mut options = UhpOptions::parse_from(["hivemind", "--evaluator", "basic"]);
+        let mut evaluator = evaluators::Dynamic::new(options.clone());
+        assert_eq!(evaluator.name(), "BasicEvaluator");
+
+        options.set("Evaluator", "mcts").unwrap();
+        evaluator.set_options(&options);
+        assert_eq!(evaluator.name(), "MctsEvaluator");
+        assert_eq!(options.describe("Evaluator").unwrap(), "Evaluator;enum;mcts;strongest;basic;linear;mcts;strongest");
+
+        assert_eq!(options.set("Evaluator", "weakest").unwrap_err().kind, Kind::ParseError);
+    }
+
+    #[test]
+    fn dynamic_evaluator_searches()
+    {
+        let _setup = setup::setup();
+
+        let options = templates::options(&["-e", "mcts"]);
+        let mut evaluator = evaluators::Dynamic::new(options);
+
+        let board = templates::run_game(bench::POSITIONS[1]);
+        let mv = evaluator.best_move(&board, SearchArgs::Nodes(16));
+        assert!(board.is_legal(&mv));
+        assert_eq!(evaluator.nodes(), 16);
+    }
+}
@@ -0,0 +1,72 @@
+mod common;
+use common::*;
+
+#[cfg(test)]
+mod linear
+{
+    use hivemind::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn features_are_from_the_movers_perspective()
+    {
+        let _setup = setup::setup();
+
+        let board = templates::run_game(bench::POSITIONS[0]);
+        let evaluators::Features(features) = evaluators::Features::extract(&board);
+        let named = |name: &str| features[evaluators::Features::NAMES.iter().position(|n| *n == name).unwrap()];
+
+        let hand = |player: Player| board.pouch().hand(player)[Bug::Ant as usize] as f64;
+        assert_eq!(named("in_hand_ant"), hand(board.to_move()) - hand(board.to_move().flip()));
+        assert_eq!(named("tempo"), 1.0);
+
+        let mobility = |player: Player| {
+            (1..=3)
+                .map(|num| Piece {
+                    player,
+                    kind: Bug::Ant,
+                    num,
+                })
+                .filter(|ant| board.location(ant).is_some_and(|hex| board.top(hex) == Some(*ant)) && !board.is_pinned(ant))
+                .map(|ant| board.mobility(&ant) as f64)
+                .sum::<f64>()
+        };
+        assert_eq!(named("mobility_ant"), mobility(board.to_move()) - mobility(board.to_move().flip()));
+    }
+
+    #[test]
+    fn linear_weights_load_by_name()
+    {
+        let _setup = setup::setup();
+
+        let path = std::env::temp_dir().join(format!("hivemind-linear-{}.json", std::process::id()));
+
+        std::fs::write(&path, r#"{ "queen_liberties": 50.0 }"#).unwrap();
+        let weights = evaluators::LinearWeights::load(&path).unwrap();
+        let defaults = evaluators::LinearWeights::default();
+
+        let i = evaluators::Features::NAMES.iter().position(|n| *n == "queen_liberties").unwrap();
+        assert_eq!(weights.0[i], 50.0);
+        assert_eq!(weights.0[i + 1], defaults.0[i + 1]);
+
+        std::fs::write(&path, r#"{ "queen_liberty": 50.0 }"#).unwrap();
+        assert_eq!(evaluators::LinearWeights::load(&path).unwrap_err().kind, Kind::InvalidOption);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn linear_evaluator_searches()
+    {
+        let _setup = setup::setup();
+
+        let options = templates::options(&[]);
+        let mut evaluator = evaluators::Linear::new(options);
+
+        let board = templates::run_game(bench::POSITIONS[2]);
+        let mv = evaluator.best_move(&board, SearchArgs::Depth(Depth::new(1)));
+        assert!(board.is_legal(&mv));
+        assert!(evaluator.nodes() > 0);
+    }
+}
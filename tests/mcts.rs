@@ -0,0 +1,75 @@
+mod common;
+use common::*;
+
+#[cfg(test)]
+mod mcts
+{
+    use hivemind::prelude::*;
+
+    use super::*;
+
+    fn evaluator(args: &[&str]) -> evaluators::Mcts
+    {
+        let mut options = templates::options(args);
+        options.num_threads = 2;
+        evaluators::Mcts::new(options)
+    }
+
+    #[test]
+    fn search_args_parse_nodes()
+    {
+        let _setup = setup::setup();
+
+        let args = SearchArgs::parse(&["nodes", "500"]).unwrap();
+        assert!(matches!(args, SearchArgs::Nodes(500)));
+        assert_eq!(args.nodes(), 500);
+        assert_eq!(args.depth(), Depth::MAX);
+
+        assert!(SearchArgs::parse(&["nodes", "many"]).is_err());
+    }
+
+    #[test]
+    fn mcts_stops_at_the_node_limit()
+    {
+        let _setup = setup::setup();
+
+        let mut evaluator = evaluator(&[]);
+        let board = templates::run_game(bench::POSITIONS[2]);
+
+        let mv = evaluator.best_move(&board, SearchArgs::Nodes(64));
+        assert!(board.is_legal(&mv));
+        assert_eq!(evaluator.nodes(), 64);
+        assert!(evaluator.memory() > 0);
+    }
+
+    #[test]
+    fn mcts_reuses_its_tree_after_moves_are_played()
+    {
+        let _setup = setup::setup();
+
+        let mut evaluator = evaluator(&["--playout", "random"]);
+        let mut board = templates::run_game(bench::POSITIONS[0]);
+
+        // Playing both sides means every search starts a move after the last one, in a part of the tree it has seen.
+        for i in 0..4
+        {
+            let mv = evaluator.best_move(&board, SearchArgs::Nodes(48));
+            assert!(board.is_legal(&mv));
+            board.play(&mv).unwrap();
+
+            // The move played was the most visited one, so its subtree carries visits over, which the count leaves out.
+            assert_eq!(evaluator.nodes(), 48);
+            if i > 0
+            {
+                assert!(evaluator.reused() > 0, "search {} started from an empty tree", i);
+            }
+            else
+            {
+                assert_eq!(evaluator.reused(), 0);
+            }
+        }
+
+        evaluator.new_game();
+        assert_eq!(evaluator.memory(), 0);
+    }
+}
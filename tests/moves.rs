@@ -0,0 +1,316 @@
+mod common;
+use common::*;
+
+#[cfg(test)]
+mod moves
+{
+    use std::collections::HashSet;
+
+    use hivemind::prelude::*;
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+    use super::*;
+
+    /// Checks that every resolved move agrees with the move generator and actually lands where it claims to.
+    fn check_resolutions(board: &Board) -> Vec<ResolvedMove>
+    {
+        let resolved = board.legal_moves_resolved(false);
+
+        let generated: HashSet<Move> = board.generate_moves(false).into_iter().collect();
+        let from_resolved: HashSet<Move> = resolved.iter().map(|r| Into::<Move>::into(*r)).collect();
+        assert_eq!(generated, from_resolved);
+        assert_eq!(resolved.len(), from_resolved.len());
+
+        for r in resolved.iter()
+        {
+            let movestr: MoveString = (*r).into();
+            let mv = Move::from(&movestr, board).unwrap();
+            assert_eq!(mv, r.mv);
+
+            let piece_location = board.location(&r.piece).map(Axial::from);
+            assert_eq!(piece_location, r.from);
+
+            let mut after = board.clone();
+            after.play(&mv).unwrap();
+            assert_eq!(after.location(&r.piece).map(Axial::from), Some(r.to));
+            assert_eq!(after.field().height(Hex::from(r.to)), Some(r.to_height));
+        }
+
+        resolved
+    }
+
+    #[test]
+    fn placements_from_pouch()
+    {
+        let _setup = setup::setup();
+        let raw_string = r"Base;InProgress;White[2];wA1;bS1 wA1-";
+        let board = templates::run_game(raw_string);
+
+        let resolved = check_resolutions(&board);
+        assert!(!resolved.is_empty());
+        assert!(resolved.iter().all(|r| r.is_placement() && r.from_height.is_none() && r.to_height == 1 && !r.throw));
+    }
+
+    #[test]
+    fn beetle_climbs()
+    {
+        let _setup = setup::setup();
+        let raw_string = r"Base;InProgress;White[4];wA1;bA1 wA1-;wQ -wA1;bQ bA1-;wB1 -wQ;bB1 bQ-";
+        let board = templates::run_game(raw_string);
+
+        let resolved = check_resolutions(&board);
+        let climbs = resolved.iter().filter(|r| r.piece.kind == Bug::Beetle && r.to_height == 2).count();
+        assert!(climbs > 0);
+    }
+
+    #[test]
+    fn pillbug_throws()
+    {
+        let _setup = setup::setup();
+        let raw_string = r"Base+P;InProgress;White[6];wP;bA1 /wP;wQ wP-;bQ /bA1;wB1 \wP;bA2 /bQ;wG1 wQ\;bA2 bA1-;wA1 \wB1;bS1 -bA1";
+        let board = templates::run_game(raw_string);
+
+        let resolved = check_resolutions(&board);
+        let throws = resolved.iter().filter(|r| r.throw).collect::<Vec<_>>();
+        assert!(!throws.is_empty());
+        assert!(throws.iter().any(|r| r.piece.player == Player::Black));
+        assert!(throws.iter().all(|r| r.piece.kind != Bug::Pillbug && r.from_height == Some(1) && r.to_height == 1));
+
+        let pillbug = Piece {
+            player: Player::White,
+            kind:   Bug::Pillbug,
+            num:    1,
+        };
+        assert_eq!(board.throw_options(&pillbug), board.generate_throws().len());
+    }
+
+    #[test]
+    fn prioritized_generation_is_complete()
+    {
+        let _setup = setup::setup();
+        let raw_string = r"Base+P;InProgress;White[6];wP;bA1 /wP;wQ wP-;bQ /bA1;wB1 \wP;bA2 /bQ;wG1 wQ\;bA2 bA1-;wA1 \wB1;bS1 -bA1";
+        let board = templates::run_game(raw_string);
+
+        let prioritized = evaluators::PrioritizingMoveGenerator::new(&board, false).collect::<Vec<_>>();
+        let unique: HashSet<Move> = prioritized.iter().copied().collect();
+        assert_eq!(prioritized.len(), unique.len());
+
+        let generated: HashSet<Move> = board.generate_moves(false).into_iter().collect();
+        assert_eq!(generated, unique);
+
+        // The picker should hand out every queen attack before anything quieter.
+        let mut picker = evaluators::MovePicker::new(false);
+        let mut stages = Vec::new();
+        while let Some(mv) = picker.next(&board, None)
+        {
+            stages.push((mv, picker.stage()));
+        }
+
+        let queen = board.queen(Player::Black).unwrap();
+        let attacks = stages.iter().filter(|(_, stage)| *stage == evaluators::Stage::Attacks).collect::<Vec<_>>();
+        assert!(!attacks.is_empty());
+        assert!(stages.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        for (mv, _) in attacks
+        {
+            let to = board.destination(mv).unwrap();
+            assert!(to == queen || hex::neighbours(queen).contains(&to));
+        }
+    }
+
+    #[test]
+    fn single_replies_are_recognised()
+    {
+        let _setup = setup::setup();
+
+        // After the lock in the last tactic, Black can only walk into it.
+        let mut board = templates::run_game(bench::TACTICS.last().unwrap().position);
+        let lock = Move::from(&"wA2 bS2/".parse().unwrap(), &board).unwrap();
+        board.play(&lock).unwrap();
+        assert_eq!(board.generate_moves(false).len(), 1);
+
+        let positions = bench::POSITIONS.iter().map(|position| templates::run_game(position));
+        for board in positions.chain([board])
+        {
+            let single = board.generate_moves(false).len() == 1;
+
+            // A fresh picker generates the movements to find out, and hands them over for caching.
+            let mut picker = evaluators::MovePicker::new(false);
+            assert_eq!(picker.is_single_reply(&board), single);
+            assert!(picker.generated_movements().is_some());
+
+            // And a picker that has already moved on to them does not need to.
+            let mut picker = evaluators::MovePicker::new(false);
+            picker.next(&board, None);
+            assert_eq!(picker.is_single_reply(&board), single);
+        }
+    }
+
+    #[test]
+    fn undo_restores_position()
+    {
+        let _setup = setup::setup();
+        let mut rng = StdRng::seed_from_u64(0xB0A7D);
+
+        for position in bench::POSITIONS
+        {
+            let mut board: Board = position.parse::<GameString>().unwrap().into();
+            let mut saved: Vec<(Board, ZobristHash)> = Vec::new();
+
+            for _ in 0..100
+            {
+                let moves = board.generate_moves(false);
+                let mv = moves.choose(&mut rng).copied().unwrap_or(Move::Pass);
+
+                let before = (board.clone(), board.zobrist());
+                if board.play(&mv).is_err() || board.state() != GameState::InProgress
+                {
+                    board = before.0;
+                    continue;
+                }
+                saved.push(before);
+            }
+
+            while let Some((before, zobrist)) = saved.pop()
+            {
+                board.undo_one().unwrap();
+                assert!(board == before, "undo disagrees with {}", before);
+                assert_eq!(board.zobrist(), zobrist, "undo changed the hash of {}", before);
+            }
+        }
+    }
+
+    #[test]
+    fn tactical_moves_are_legal()
+    {
+        let _setup = setup::setup();
+        let mut rng = StdRng::seed_from_u64(0x7AC7);
+
+        // Black just placed their Pillbug's only other neighbour, so extending the placement would split the hive.
+        let pinned = r"Base+LMP;InProgress;Black[9];wL;bP wL/;wQ -wL;bG1 bP/;wM wQ\;bL bP-;wS1 \wQ;bQ \bG1;wM wL-;bB1 bL-;wS2 wL\;bB2 bB1/;wS1 -bQ;bM bB2-;wG1 /wS2;bA1 bG1-;wM wG1\";
+        let positions = bench::POSITIONS.iter().chain([&pinned]);
+
+        for position in positions
+        {
+            let mut board = templates::run_game(position);
+
+            for _ in 0..60
+            {
+                let tactical = board.generate_tactical_moves();
+                for mv in tactical.iter()
+                {
+                    assert!(board.is_legal(mv), "{} is not legal in {}", mv, GameString::from(&board));
+                }
+
+                let moves = board.generate_moves(false);
+                let generated: HashSet<Move> = tactical.into_iter().collect();
+                let filtered: HashSet<Move> = moves.iter().copied().filter(|mv| board.is_tactical(mv)).collect();
+                assert_eq!(generated, filtered, "in {}", GameString::from(&board));
+                let Some(mv) = moves.choose(&mut rng).copied()
+                else
+                {
+                    break;
+                };
+
+                if board.play(&mv).is_err() || board.state() != GameState::InProgress
+                {
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tactical_moves_are_queen_tactics()
+    {
+        let _setup = setup::setup();
+
+        let mut tactics = 0;
+        for position in bench::POSITIONS
+        {
+            let board = templates::run_game(position);
+
+            let generated: HashSet<Move> = board.generate_tactical_moves().into_iter().collect();
+            let filtered: HashSet<Move> = board.generate_moves(false).into_iter().filter(|mv| board.is_tactical(mv)).collect();
+            assert_eq!(generated, filtered, "in {}", position);
+
+            // Every move that fills a liberty of the enemy queen is tactical.
+            let enemy_queen = board.queen(board.to_move().flip()).unwrap();
+            for mv in board.generate_movements()
+            {
+                let Move::Move(piece, _) = mv
+                else
+                {
+                    continue;
+                };
+                let from = board.location(&piece).unwrap();
+                let to = board.destination(&mv).unwrap();
+                if hex::neighbours(enemy_queen).contains(&to) && !hex::neighbours(enemy_queen).contains(&from)
+                {
+                    assert!(generated.contains(&mv), "{} is not tactical in {}", mv, position);
+                }
+            }
+
+            tactics += generated.len();
+        }
+        assert!(tactics > 0);
+    }
+
+    #[test]
+    fn tactical_moves_match_hand_picked_sets()
+    {
+        let _setup = setup::setup();
+
+        let cases: [(&str, &[&str]); 5] = [
+            // The Beetle can climb its own queen; stepping around her keeps the liberty filled.
+            (r"Base;InProgress;White[4];wA1;bA1 wA1-;wQ -wA1;bQ bA1-;wB1 -wQ;bB1 bQ-", &["wB1 wQ"]),
+            // With White's Beetle on their own queen, only Black's mirror climb is left.
+            (r"Base;InProgress;Black[4];wA1;bA1 wA1-;wQ -wA1;bQ bA1-;wB1 -wQ;bB1 bQ-;wB1 wQ", &["bB1 bQ"]),
+            // The Grasshopper is the only free bug, and any jump off its queen's liberty counts.
+            (r"Base+P;InProgress;White[4];wP;bA1 wP-;wQ -wP;bQ bA1-;wG1 -wQ;bB1 bQ-", &["wG1 bB1-"]),
+            // Black's only tactics are Pillbug throws of the White Ant beside it.
+            (
+                r"Base+P;InProgress;Black[6];wA1;bP wA1-;wB1 /wA1;bQ bP-;wS1 -wA1;bQ wB1-;wQ /wB1;bG1 bP/;wA2 wQ\;bS1 bG1/;wA2 bQ\",
+                &["wA1 bP-", "wA1 bQ-", "wA1 -bG1"],
+            ),
+            // Ant fills, queen moves, and throws of the enemy Pillbug by White's own.
+            (
+                r"Base+P;InProgress;White[6];wP;bP wP/;wS1 /wP;bG1 \bP;wA1 -wP;bA1 -bG1;wQ /wA1;bQ bG1-;wA1 bA1\;bA2 \bA1",
+                &["wA1 bQ/", "wA1 bP-", r"wA1 \bQ", "wA1 bQ-", "wQ /wS1", "wQ /wA1", "bP wP-", "bP wS1-", "bP /wA1"],
+            ),
+        ];
+
+        for (position, expected) in cases
+        {
+            let board = templates::run_game(position);
+
+            let generated: HashSet<Move> = board.generate_tactical_moves().into_iter().collect();
+            let expected: HashSet<Move> =
+                expected.iter().map(|mv| Move::from(&mv.parse::<MoveString>().unwrap(), &board).unwrap()).collect();
+            assert_eq!(generated, expected, "in {}", position);
+        }
+    }
+
+    #[test]
+    fn mobility_counts_destinations()
+    {
+        let _setup = setup::setup();
+
+        for position in bench::POSITIONS
+        {
+            let board = templates::run_game(position);
+
+            let mut destinations: HashSet<(Piece, Hex)> = HashSet::new();
+            for mv in board.generate_movements()
+            {
+                if let Move::Move(piece, _) = mv
+                {
+                    destinations.insert((piece, board.destination(&mv).unwrap()));
+                }
+            }
+
+            let ours = destinations.iter().map(|(piece, _)| *piece).collect::<HashSet<Piece>>();
+            let mobility = ours.iter().map(|piece| board.mobility(piece)).sum::<usize>();
+            assert_eq!(mobility, destinations.len(), "in {}", position);
+        }
+    }
+}
@@ -0,0 +1,192 @@
+mod common;
+use common::*;
+
+#[cfg(test)]
+mod params
+{
+    use clap::Parser;
+    use hivemind::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn weights_load_from_files()
+    {
+        let _setup = setup::setup();
+
+        let dir = std::env::temp_dir();
+        let json = dir.join(format!("hivemind-params-{}.json", std::process::id()));
+        let toml = dir.join(format!("hivemind-params-{}.toml", std::process::id()));
+
+        std::fs::write(&json, r#"{ "value_ant": 3.5 }"#).unwrap();
+        std::fs::write(&toml, "k_defense = 12.0\n").unwrap();
+
+        // Weights that are not named keep their defaults.
+        let defaults = evaluators::EvalParams::default();
+        let from_json = evaluators::EvalParams::load(&json).unwrap();
+        assert_eq!(from_json.value_ant, 3.5);
+        assert_eq!(from_json.k_defense, defaults.k_defense);
+
+        let from_toml = evaluators::EvalParams::load(&toml).unwrap();
+        assert_eq!(from_toml.k_defense, 12.0);
+        assert_eq!(from_toml.value_ant, defaults.value_ant);
+
+        std::fs::write(&json, r#"{ "value_ants": 3.5 }"#).unwrap();
+        let err = evaluators::EvalParams::load(&json).unwrap_err();
+        assert_eq!(err.kind, Kind::InvalidOption);
+
+        std::fs::remove_file(&json).unwrap();
+        std::fs::remove_file(&toml).unwrap();
+    }
+
+    #[test]
+    fn weights_are_options()
+    {
+        let _setup = setup::setup();
+
+        let mut options = UhpOptions::parse_from(["hivemind"]);
+        assert_eq!(UhpOptions::names().count(), UhpOptions::NAMES.len() + evaluators::EvalParams::NAMES.len());
+
+        options.set("ValueQueen", "20").unwrap();
+        assert_eq!(options.eval.value_queen, 20.0);
+        assert_eq!(options.describe("ValueQueen").unwrap(), "ValueQueen;double;20;12;-1000;1000");
+
+        assert_eq!(options.set("ValueQueen", "1e6").unwrap_err().kind, Kind::InvalidOption);
+        assert_eq!(options.set("ValueKing", "1").unwrap_err().kind, Kind::InvalidOption);
+    }
+
+    #[test]
+    fn weights_taper_by_phase()
+    {
+        let _setup = setup::setup();
+
+        let params = evaluators::EvalParams::default();
+        assert_eq!(params.tapered(0.0).k_race, params.k_race);
+        assert_eq!(params.tapered(1.0).k_race, params.endgame_k_race);
+        assert_eq!(params.tapered(0.5).k_race, (params.k_race + params.endgame_k_race) / 2.0);
+
+        let mut options = UhpOptions::parse_from(["hivemind"]);
+        options.set("EndgameValueQueen", "20").unwrap();
+        assert_eq!(options.eval.endgame_value_queen, 20.0);
+        assert_eq!(options.eval.value_queen, params.value_queen);
+
+        let opening = templates::run_game(bench::POSITIONS[0]);
+        let phases = bench::POSITIONS.iter().map(|position| evaluators::Strongest::phase(&templates::run_game(position)));
+        for phase in phases
+        {
+            assert!((0.0..=1.0).contains(&phase));
+        }
+        assert!(evaluators::Strongest::phase(&opening) < 0.5);
+    }
+
+    #[test]
+    fn evaluation_reads_the_tapered_weights()
+    {
+        let _setup = setup::setup();
+
+        // Copies the opening half of the weights over the endgame half, so that tapering leaves them as they are.
+        let flatten = |params: &evaluators::EvalParams| {
+            let mut flat = params.clone();
+            let (openings, endgames) = evaluators::EvalParams::NAMES.split_at(evaluators::EvalParams::TERMS);
+            for (opening, endgame) in openings.iter().zip(endgames)
+            {
+                *flat.get_mut(endgame).unwrap() = params.get(opening).unwrap();
+            }
+            flat
+        };
+
+        let board = templates::run_game(bench::POSITIONS[3]);
+        let phase = evaluators::Strongest::phase(&board);
+        assert!(0.0 < phase && phase < 1.0);
+
+        // The defaults evaluate like their own blend at this phase, up to the rounding of the blend itself.
+        let params = evaluators::EvalParams::default();
+        let score = evaluators::Strongest::evaluate_board(&params, &board);
+        let blended = evaluators::Strongest::evaluate_board(&flatten(&params.tapered(phase)), &board);
+        assert!((score - blended).abs() <= 1, "{} against {}", score, blended);
+
+        // So the endgame weights count: without them, the score is that of the opening weights alone.
+        let opening = evaluators::Strongest::evaluate_board(&flatten(&params), &board);
+        assert!((score - opening).abs() > 1, "{} against {}", score, opening);
+    }
+
+    #[test]
+    fn sides_can_use_different_weights()
+    {
+        let _setup = setup::setup();
+
+        // The second side reads its weights from a file, as in the arena, and they make Ants worth nothing.
+        let path = std::env::temp_dir().join(format!("hivemind-side-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{ "value_ant": 0.0 }"#).unwrap();
+
+        let side = |eval_params: Option<std::path::PathBuf>| {
+            let mut options = templates::options(&[]);
+            options.eval_params = eval_params;
+            options.load_weights().unwrap();
+            evaluators::Strongest::new(options)
+        };
+        let mut first = side(None);
+        let mut second = side(Some(path.clone()));
+        std::fs::remove_file(&path).unwrap();
+
+        // Black's Ant beside White's Ant is worth keeping, unless Ants are worth nothing, when it goes for the queen. The
+        // sides take turns as in a game, so neither may pick up what the other has evaluated.
+        let board = templates::run_game(bench::POSITIONS[1]);
+        let args = SearchArgs::Depth(Depth::new(1));
+        let before = first.best_move(&board, args);
+        let other = second.best_move(&board, args);
+        let after = first.best_move(&board, args);
+        assert_eq!(before, after);
+        assert_eq!(other, Move::from(&"bA1 -wQ".parse().unwrap(), &board).unwrap());
+        assert_ne!(before, other);
+    }
+
+    #[test]
+    fn tuning_lowers_the_loss()
+    {
+        let _setup = setup::setup();
+
+        let games = bench::POSITIONS
+            .iter()
+            .enumerate()
+            .map(|(i, position)| format!("{} {}", if i % 2 == 0 { "1-0" } else { "0-1" }, position))
+            .map(|line| line.parse::<GameRecord>().unwrap())
+            .collect::<Vec<_>>();
+
+        let written = games[1].to_string();
+        assert!(written.starts_with("0-1 "));
+        assert_eq!(written.parse::<GameRecord>().unwrap().result, 0.0);
+
+        // An unfinished game needs to be given a result.
+        assert_eq!(bench::POSITIONS[0].parse::<GameRecord>().unwrap_err().kind, Kind::ParseError);
+
+        let params = evaluators::EvalParams::default();
+        let mut tuner = evaluators::Tuner::new(&params, &games, 8, 2);
+        assert!(!tuner.is_empty());
+        tuner.fit_scale(&params);
+
+        // In the tactics, the side to move wins. With every weight at zero, every position evaluates to a draw, which
+        // costs exactly ln 2 whatever the scale.
+        let tactics = bench::TACTICS
+            .iter()
+            .map(|tactic| {
+                let winner = if tactic.position.contains(";White[") { "1-0" } else { "0-1" };
+                format!("{} {}", winner, tactic.position).parse::<GameRecord>().unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let mut zero = params.clone();
+        for weight in zero.values_mut()
+        {
+            *weight = 0.0;
+        }
+
+        let tuner = evaluators::Tuner::new(&zero, &tactics, 12, 2);
+        assert!((tuner.loss(&zero) - std::f64::consts::LN_2).abs() < 1e-9);
+
+        // Fitting finds that a queen further from being surrounded is a queen on the winning side.
+        let tuned = tuner.tune(&zero, 3, |_, _, _| {});
+        assert!(tuner.loss(&tuned) < 0.68, "loss {}", tuner.loss(&tuned));
+        assert!(tuned.k_race > 0.0 && tuned.endgame_k_race > 0.0);
+    }
+}
@@ -0,0 +1,70 @@
+mod common;
+use common::*;
+
+#[cfg(test)]
+mod pins
+{
+    use hivemind::prelude::*;
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+    use super::*;
+
+    /// Checks that every lone piece on the ground is pinned exactly when its hex is a cut vertex of the hive.
+    fn check_pins(board: &Board)
+    {
+        let pins = board.field().find_pins();
+
+        for i in 0..piece::consts::COUNT
+        {
+            let piece = Piece::from(i);
+            let Some(hex) = board.location(&piece)
+            else
+            {
+                continue;
+            };
+
+            if board.top(hex) == Some(piece) && !board.stacked(&piece)
+            {
+                assert_eq!(board.is_pinned(&piece), pins.contains(hex), "{} disagrees on {}", board, piece);
+            }
+        }
+    }
+
+    #[test]
+    fn random_games_keep_pins()
+    {
+        let _setup = setup::setup();
+        let mut rng = StdRng::seed_from_u64(0x5EED);
+
+        for _ in 0..20
+        {
+            let mut board = templates::run_game("Base+LMP;NotStarted;White[1]");
+            let mut played = 0;
+
+            for _ in 0..60
+            {
+                let moves = board.generate_moves(false);
+                let Some(mv) = moves.choose(&mut rng)
+                else
+                {
+                    break;
+                };
+
+                if board.play(mv).is_err() || board.state() != GameState::InProgress
+                {
+                    break;
+                }
+
+                played += 1;
+                check_pins(&board);
+            }
+
+            // Undoing has to bring back the same pins that playing found.
+            for _ in 0..played / 2
+            {
+                board.undo_one().unwrap();
+                check_pins(&board);
+            }
+        }
+    }
+}
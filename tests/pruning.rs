@@ -0,0 +1,124 @@
+mod common;
+use common::*;
+
+#[cfg(test)]
+mod pruning
+{
+    use clap::Parser;
+    use hivemind::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn pruning_is_an_option()
+    {
+        let _setup = setup::setup();
+
+        let mut options = UhpOptions::parse_from(["hivemind"]);
+        assert_eq!(options.describe("Pruning").unwrap(), "Pruning;bool;true;true");
+
+        options.set("Pruning", "false").unwrap();
+        assert!(!options.pruning);
+        assert_eq!(options.set("Pruning", "maybe").unwrap_err().kind, Kind::ParseError);
+
+        assert!(!UhpOptions::parse_from(["hivemind", "--no-pruning"]).pruning);
+    }
+
+    #[test]
+    fn pruning_searches_fewer_nodes()
+    {
+        let _setup = setup::setup();
+
+        let raw_string = r"Base;InProgress;White[4];wA1;bA1 wA1-;wQ -wA1;bQ bA1-;wB1 -wQ;bB1 bQ-";
+        let board = templates::run_game(raw_string);
+
+        let nodes = [false, true].map(|pruning| {
+            let mut options = templates::options(&[]);
+            options.pruning = pruning;
+
+            let mut evaluator = evaluators::Strongest::new(options);
+            let mv = evaluator.best_move(&board, SearchArgs::Depth(Depth::new(3)));
+            assert!(board.is_legal(&mv));
+            evaluator.nodes()
+        });
+
+        assert!(nodes[1] < nodes[0], "{} nodes with pruning, {} without", nodes[1], nodes[0]);
+    }
+
+    #[test]
+    fn pruning_solves_the_tactics()
+    {
+        let _setup = setup::setup();
+
+        let options = templates::options(&[]);
+        let mut evaluator = evaluators::Strongest::new(options);
+
+        let mut missed = Vec::new();
+        let solved = bench::tactics(&mut evaluator, Depth::new(2), |i, mv, solves| {
+            if !solves
+            {
+                missed.push(format!("{} in position {}", mv, i + 1));
+            }
+        })
+        .unwrap();
+
+        assert_eq!(solved, bench::TACTICS.len(), "played {:?}", missed);
+    }
+
+    /// Searches a tactic as deep as it needs, returning whether the move found solves it and the telemetry record.
+    fn solve(test: &str, tactic: &bench::Tactic) -> (bool, serde_json::Value)
+    {
+        let board = templates::run_game(tactic.position);
+        let (mv, record) = templates::search_with_telemetry(test, &board, tactic.depth);
+
+        let solves = tactic.solutions.iter().any(|solution| Move::from(&solution.parse().unwrap(), &board).unwrap() == mv);
+        (solves, record)
+    }
+
+    #[test]
+    fn pruning_nulls_the_loose_tactics()
+    {
+        let _setup = setup::setup();
+
+        // With both queens far from surrounded, the null move can be tried wherever the search is deep enough.
+        let loose = bench::TACTICS
+            .iter()
+            .filter(|tactic| tactic.depth >= Depth::new(4))
+            .filter(|tactic| {
+                let board = templates::run_game(tactic.position);
+                [Player::White, Player::Black]
+                    .into_iter()
+                    .filter_map(|player| board.queen(player))
+                    .all(|queen| board.field().neighbours(queen).len() < 4)
+            })
+            .collect::<Vec<_>>();
+        assert!(!loose.is_empty());
+
+        // A lock where every reply is forced is found by the single reply extension before any node is deep enough to
+        // pass, so the null move only has to fire on the way to some of them.
+        let nulled = loose
+            .into_iter()
+            .map(|tactic| {
+                let (solves, record) = solve("nulls", tactic);
+                assert!(solves, "missed {}", tactic.position);
+                record["pruning"]["nulled"].as_u64().unwrap()
+            })
+            .sum::<u64>();
+        assert!(nulled > 0);
+    }
+
+    #[test]
+    fn pruning_keeps_the_zugzwang()
+    {
+        let _setup = setup::setup();
+
+        // Black has nothing in hand and only two free bugs, so their only reply to the lock walks into it. A null move
+        // for Black would let them stand still instead, and only the zugzwang guard keeps the search from believing it.
+        let tactic = bench::TACTICS.last().unwrap();
+        let (solves, record) = solve("zugzwang", tactic);
+
+        assert!(solves, "missed {}", tactic.position);
+        assert!(record["pruning"]["nulled"].as_u64().unwrap() > 0);
+        assert!(record["pruning"]["zugzwang"].as_u64().unwrap() > 0);
+    }
+}
@@ -0,0 +1,82 @@
+mod common;
+use common::*;
+
+#[cfg(test)]
+mod skill
+{
+    use clap::Parser;
+    use hivemind::prelude::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn scored(score: i32) -> ScoredMove
+    {
+        ScoredMove {
+            mv: Move::Pass,
+            score,
+        }
+    }
+
+    #[test]
+    fn full_strength_has_no_handicap()
+    {
+        let _setup = setup::setup();
+
+        let skill = evaluators::Skill::new(evaluators::Skill::MAX);
+        assert!(!skill.is_limited());
+        assert_eq!(skill.candidates(), 1);
+        assert_eq!(skill.depth(), Depth::MAX);
+        assert_eq!(skill.nodes(), u64::MAX);
+
+        let moves = [scored(30), scored(20), scored(10)];
+        assert_eq!(skill.pick(&moves, &mut StdRng::seed_from_u64(0)).unwrap().score, 30);
+    }
+
+    #[test]
+    fn weak_levels_pick_among_the_best_moves()
+    {
+        let _setup = setup::setup();
+
+        let weakest = evaluators::Skill::new(0);
+        let stronger = evaluators::Skill::new(16);
+        assert!(weakest.candidates() > stronger.candidates());
+        assert!(weakest.depth() < stronger.depth());
+        assert!(weakest.nodes() < stronger.nodes());
+
+        let moves = (0..10).map(|i| scored(-10 * i)).collect::<Vec<_>>();
+        let mut rng = StdRng::seed_from_u64(7);
+        let picks = (0..200).map(|_| weakest.pick(&moves, &mut rng).unwrap().score).collect::<Vec<_>>();
+
+        let worst_candidate = moves[weakest.candidates() - 1].score;
+        assert!(picks.iter().all(|score| *score >= worst_candidate));
+        assert!(picks.iter().any(|score| *score != 0));
+    }
+
+    #[test]
+    fn skill_level_is_an_option()
+    {
+        let _setup = setup::setup();
+
+        let mut options = UhpOptions::parse_from(["hivemind"]);
+        assert_eq!(options.describe("SkillLevel").unwrap(), "SkillLevel;int;20;20;0;20");
+
+        options.set("SkillLevel", "3").unwrap();
+        assert_eq!(options.skill(), evaluators::Skill::new(3));
+        assert_eq!(options.set("SkillLevel", "21").unwrap_err().kind, Kind::InvalidOption);
+    }
+
+    #[test]
+    fn handicapped_search_stays_within_its_caps()
+    {
+        let _setup = setup::setup();
+
+        let options = templates::options(&["--skill-level", "2"]);
+        let mut evaluator = evaluators::Strongest::new(options.clone());
+
+        let board = templates::run_game(bench::POSITIONS[3]);
+        let mv = evaluator.best_move(&board, SearchArgs::Depth(Depth::new(6)));
+        assert!(board.is_legal(&mv));
+        assert!(evaluator.depth() <= options.skill().depth());
+    }
+}
@@ -0,0 +1,116 @@
+mod common;
+use common::*;
+
+#[cfg(test)]
+mod table
+{
+    use hivemind::prelude::*;
+
+    use super::*;
+
+    fn entry(key: ZobristHash, mv: Move, depth: i32, score: i32) -> TTEntry
+    {
+        TTEntry {
+            key,
+            mv: mv.into(),
+            depth: Depth::new(depth),
+            score,
+            age: TTAge::compute(score, score - 1, score + 1),
+        }
+    }
+
+    /// Spreads small numbers across the table.
+    fn spread(n: usize) -> ZobristHash
+    {
+        (n as ZobristHash + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15_F39C_C060_5CED_C835)
+    }
+
+    #[test]
+    fn tokens_round_trip()
+    {
+        let _setup = setup::setup();
+
+        for position in bench::POSITIONS
+        {
+            let board = templates::run_game(position);
+
+            for mv in board.generate_moves(false).into_iter().chain([Move::Pass])
+            {
+                let token = MoveToken::from(mv);
+                assert!(token.is_some());
+                assert_eq!(Option::<Move>::from(token), Some(mv));
+            }
+        }
+
+        assert_eq!(Option::<Move>::from(MoveToken::default()), None);
+    }
+
+    #[test]
+    fn keys_are_verified()
+    {
+        let _setup = setup::setup();
+
+        // A single bucket, so that every key collides.
+        let table = TranspositionTable::new(64);
+        let board = templates::run_game(bench::POSITIONS[0]);
+        let mv = board.generate_moves(false)[0];
+
+        table.store(&entry(1, mv, 3, 42));
+        assert!(table.load(2).is_none());
+
+        let hit = table.load(1).unwrap();
+        assert_eq!(Option::<Move>::from(hit.mv), Some(mv));
+        assert_eq!(hit.depth, Depth::new(3));
+        assert_eq!(hit.score, 42);
+        assert_eq!(hit.bound, TTBound::Exact);
+
+        // Negative scores survive the packing, and a shallower entry does not replace a deeper one.
+        table.store(&entry(1, Move::Pass, 2, -MINIMUM_WIN));
+        assert_eq!(table.load(1).unwrap().score, 42);
+        table.store(&entry(1, Move::Pass, 5, -MINIMUM_WIN));
+        assert_eq!(table.load(1).unwrap().score, -MINIMUM_WIN);
+
+        // Filling the bucket evicts the shallowest entry.
+        for key in 2..=TranspositionTable::BUCKET_SIZE as ZobristHash + 1
+        {
+            table.store(&entry(key, mv, key as i32 + 10, 0));
+        }
+        assert!(table.load(1).is_none());
+        assert_eq!(table.hashfull(), 1000);
+    }
+
+    #[test]
+    fn tables_survive_a_round_trip()
+    {
+        let _setup = setup::setup();
+
+        let path = std::env::temp_dir().join(format!("hivemind-table-{}.bin", std::process::id()));
+        let board = templates::run_game(bench::POSITIONS[1]);
+        let moves = board.generate_moves(false);
+
+        let saved = TranspositionTable::new(1 << 16);
+        for (key, mv) in moves.iter().enumerate()
+        {
+            saved.store(&entry(spread(key), *mv, key as i32, -(key as i32)));
+        }
+        assert_eq!(saved.save(&path).unwrap(), moves.len());
+
+        // A table of a different size still finds every entry.
+        let restored = TranspositionTable::new(1 << 20);
+        assert_eq!(restored.restore(&path).unwrap(), moves.len());
+
+        for (key, mv) in moves.iter().enumerate()
+        {
+            let hit = restored.load(spread(key)).unwrap();
+            assert_eq!(Option::<Move>::from(hit.mv), Some(*mv));
+            assert_eq!(hit.score, -(key as i32));
+        }
+
+        std::fs::write(&path, b"not a table").unwrap();
+        let err = restored.restore(&path).unwrap_err();
+        assert_eq!(err.kind, Kind::ParseError);
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(!restored.restore(&path).unwrap_err().fatal());
+    }
+}